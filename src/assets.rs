@@ -0,0 +1,13 @@
+// src/assets.rs
+
+use std::path::{Path, PathBuf};
+
+/// Resolves an asset path such as `assets/images`, so the game finds its assets
+/// whatever directory it is launched from. A shipped build keeps `assets` next to
+/// the executable; otherwise they are read from the crate's source tree.
+pub fn path(relative: &str) -> PathBuf {
+    let beside_exe = std::env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .filter(|dir| dir.join(relative).exists());
+    beside_exe.unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR"))).join(relative)
+}
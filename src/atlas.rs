@@ -0,0 +1,130 @@
+// src/atlas.rs
+
+use std::collections::HashMap;
+use std::path::Path;
+use image::{RgbaImage, Rgba, GenericImage};
+
+/// The directory every sprite image is loaded from, resolved through `assets::path`.
+pub const IMAGES_DIR: &str = "assets/images";
+
+/// The region every plain, untextured quad samples: a single opaque white texel,
+/// so the quad's color comes through unchanged.
+pub const WHITE_REGION: &str = "white";
+
+/// The width of the atlas texture. It grows downwards as images are packed in.
+const ATLAS_WIDTH: u32 = 256;
+/// Empty texels left between packed images, so neighbours never bleed into each other.
+const PADDING: u32 = 1;
+
+/// Where one image lives inside the atlas texture.
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasRegion {
+    /// The top-left corner of the region, in texture coordinates (0.0 to 1.0).
+    pub uv_min: [f32; 2],
+    /// The size of the region, in texture coordinates.
+    pub uv_size: [f32; 2],
+    /// The size of the original image, in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    /// The region as `[u, v, width, height]`, the layout the shader expects.
+    pub fn uv_rect(&self) -> [f32; 4] {
+        [self.uv_min[0], self.uv_min[1], self.uv_size[0], self.uv_size[1]]
+    }
+}
+
+/// A resource mapping sprite names to regions of the single atlas texture.
+#[derive(Default)]
+pub struct TextureAtlas {
+    pub regions: HashMap<String, AtlasRegion>,
+}
+
+impl TextureAtlas {
+    /// Looks up a region by name, falling back to the plain white region so a
+    /// missing image shows up as a flat quad rather than a crash.
+    pub fn region(&self, name: &str) -> AtlasRegion {
+        self.regions.get(name)
+            .or_else(|| self.regions.get(WHITE_REGION))
+            .copied()
+            .unwrap_or(AtlasRegion { uv_min: [0.0, 0.0], uv_size: [0.0, 0.0], width: 1, height: 1 })
+    }
+
+    /// Loads every PNG under `dir` and packs them into one atlas image. Each image's
+    /// region is named after its path relative to `dir`, without the extension, so
+    /// `assets/images/tiles/wall.png` becomes `tiles/wall`.
+    pub fn load_dir(dir: &Path) -> (Self, RgbaImage) {
+        let mut images = vec![(WHITE_REGION.to_string(), RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255])))];
+        collect_images(dir, dir, &mut images);
+        log::info!("[Atlas] Packing {} images from {}", images.len(), dir.display());
+        Self::pack(images)
+    }
+
+    /// Packs the images into rows, tallest first, and records where each one landed.
+    fn pack(mut images: Vec<(String, RgbaImage)>) -> (Self, RgbaImage) {
+        images.sort_by_key(|(_, image)| std::cmp::Reverse(image.height()));
+
+        // --- Lay out the rows ---
+        let mut placements = Vec::with_capacity(images.len());
+        let (mut x, mut y, mut row_height) = (PADDING, PADDING, 0);
+        for (_, image) in &images {
+            let width = image.width().min(ATLAS_WIDTH - PADDING * 2);
+            if x + width + PADDING > ATLAS_WIDTH {
+                x = PADDING;
+                y += row_height + PADDING;
+                row_height = 0;
+            }
+            placements.push((x, y));
+            x += width + PADDING;
+            row_height = row_height.max(image.height());
+        }
+        let height = (y + row_height + PADDING).next_power_of_two();
+
+        // --- Copy the pixels in ---
+        let mut atlas_image = RgbaImage::new(ATLAS_WIDTH, height);
+        let mut atlas = TextureAtlas::default();
+        for ((name, image), (x, y)) in images.into_iter().zip(placements) {
+            if atlas_image.copy_from(&image, x, y).is_err() {
+                log::warn!("[Atlas] {} does not fit in the atlas and was skipped", name);
+                continue;
+            }
+            atlas.regions.insert(name, AtlasRegion {
+                uv_min: [x as f32 / ATLAS_WIDTH as f32, y as f32 / height as f32],
+                uv_size: [image.width() as f32 / ATLAS_WIDTH as f32, image.height() as f32 / height as f32],
+                width: image.width(),
+                height: image.height(),
+            });
+        }
+
+        (atlas, atlas_image)
+    }
+}
+
+/// Recursively loads every PNG in `dir`, naming each after its path relative to `root`.
+fn collect_images(root: &Path, dir: &Path, images: &mut Vec<(String, RgbaImage)>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("[Atlas] Could not read {}: {}", dir.display(), e);
+            return;
+        }
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_images(root, &path, images);
+            continue;
+        }
+        if path.extension().and_then(|ext| ext.to_str()) != Some("png") {
+            continue;
+        }
+
+        let name = path.strip_prefix(root).unwrap_or(&path).with_extension("");
+        let name = name.to_string_lossy().replace('\\', "/");
+        match image::open(&path) {
+            Ok(image) => images.push((name, image.to_rgba8())),
+            Err(e) => log::warn!("[Atlas] Could not load {}: {}", path.display(), e),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// src/audio.rs
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use kira::manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings};
+use kira::sound::static_sound::StaticSoundData;
+
+/// Where the sound effects live, relative to the assets root.
+pub const SOUNDS_DIR: &str = "assets/sounds";
+
+/// A resource that plays one-shot sound effects. Without an audio device it
+/// holds no manager and every `play` is silently ignored.
+#[derive(Default)]
+pub struct Audio {
+    manager: Option<AudioManager<DefaultBackend>>,
+    /// Every loaded sound, keyed by its file name without the extension.
+    sounds: HashMap<String, StaticSoundData>,
+}
+
+impl Audio {
+    /// Opens the default audio device and loads every `.wav` file in `dir`.
+    pub fn load_dir(dir: &Path) -> Self {
+        let manager = match AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()) {
+            Ok(manager) => manager,
+            Err(e) => {
+                log::warn!("[Audio] No audio output, sound is disabled: {}", e);
+                return Self::default();
+            }
+        };
+
+        let mut sounds = HashMap::new();
+        match std::fs::read_dir(dir) {
+            Ok(entries) => {
+                for path in entries.flatten().map(|entry| entry.path()) {
+                    if path.extension().and_then(|ext| ext.to_str()) != Some("wav") {
+                        continue;
+                    }
+                    let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else { continue };
+                    match StaticSoundData::from_file(&path) {
+                        Ok(sound) => {
+                            sounds.insert(name.to_string(), sound);
+                        }
+                        Err(e) => log::warn!("[Audio] Could not load {}: {}", path.display(), e),
+                    }
+                }
+            }
+            Err(e) => log::warn!("[Audio] Could not read {}: {}", dir.display(), e),
+        }
+        log::info!("[Audio] Loaded {} sounds from {}", sounds.len(), dir.display());
+
+        Self { manager: Some(manager), sounds }
+    }
+
+    /// Plays the named sound once. A sound that failed to load is skipped.
+    pub fn play(&mut self, name: &str) {
+        let (Some(manager), Some(sound)) = (self.manager.as_mut(), self.sounds.get(name)) else { return };
+        if let Err(e) = manager.play(sound.clone()) {
+            log::warn!("[Audio] Could not play {}: {}", name, e);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+// src/color.rs
+
+// Every color in the game is authored in sRGB, the way image editors and color pickers
+// show it: `Renderable` colors, particle colors, light colors and colors in level
+// files alike. Rendering happens in linear light, so colors are converted on their
+// way in. Quad colors are converted by shader.wgsl, with the functions in color.wgsl;
+// anything set up on the CPU goes through these helpers.
+
+/// Converts one sRGB-encoded channel to linear light.
+fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts an sRGB color to linear light.
+pub fn to_linear(color: [f32; 3]) -> [f32; 3] {
+    color.map(srgb_to_linear)
+}
@@ -1,16 +1,84 @@
 // src/components.rs
 
-use specs::{Component, VecStorage, NullStorage};
+use std::collections::HashMap;
+use specs::{Component, Entity, VecStorage, NullStorage};
 use specs_derive::Component;
-use rapier2d::prelude::{RigidBodyHandle, ColliderHandle};
+use rapier2d::prelude::{RigidBodyHandle, ColliderHandle, ImpulseJointHandle, Group, InteractionGroups};
 use rapier2d::na::Vector2;
 // **NEW IMPORT**
 use rapier2d::control::KinematicCharacterController;
 
 /// A component representing an entity's position in the game world.
+/// It also remembers where the entity was on the previous physics tick, so the
+/// renderer can draw it smoothly between ticks.
 #[derive(Component, Debug)]
 #[storage(VecStorage)]
-pub struct Position(pub Vector2<f32>);
+pub struct Position {
+    pub current: Vector2<f32>,
+    pub previous: Vector2<f32>,
+}
+
+impl Position {
+    /// A position that has not moved since the previous tick.
+    pub fn new(position: Vector2<f32>) -> Self {
+        Self { current: position, previous: position }
+    }
+
+    /// Blends the previous and current positions. An `alpha` of 0.0 gives the
+    /// previous position and 1.0 gives the current one.
+    pub fn interpolated(&self, alpha: f32) -> Vector2<f32> {
+        self.previous.lerp(&self.current, alpha)
+    }
+}
+
+/// A component representing an entity's rotation in radians, counter-clockwise.
+/// Like `Position`, it remembers the previous tick's value for interpolation.
+#[derive(Component, Debug, Default)]
+#[storage(VecStorage)]
+pub struct Rotation {
+    pub current: f32,
+    pub previous: f32,
+}
+
+impl Rotation {
+    /// A rotation that has not changed since the previous tick.
+    pub fn new(angle: f32) -> Self {
+        Self { current: angle, previous: angle }
+    }
+
+    /// Blends the previous and current angles the short way round. An `alpha` of
+    /// 0.0 gives the previous angle and 1.0 gives the current one.
+    pub fn interpolated(&self, alpha: f32) -> f32 {
+        let delta = (self.current - self.previous + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        self.previous + delta * alpha
+    }
+}
+
+/// The layers quads are drawn in, back to front. Quads within a layer are drawn in
+/// no particular order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum RenderLayer {
+    Background,
+    #[default]
+    Level,
+    Props,
+    Player,
+    Foreground,
+}
+
+impl RenderLayer {
+    /// Looks up a layer by the name used in level files.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "background" => Some(Self::Background),
+            "level" => Some(Self::Level),
+            "props" => Some(Self::Props),
+            "player" => Some(Self::Player),
+            "foreground" => Some(Self::Foreground),
+            _ => None,
+        }
+    }
+}
 
 /// A component that makes an entity renderable as a colored quad.
 #[derive(Component, Debug)]
@@ -19,6 +87,322 @@ pub struct Renderable {
     pub color: [f32; 4],
     pub width: f32,
     pub height: f32,
+    /// The layer the quad is drawn in, which decides what it covers and what covers it.
+    pub layer: RenderLayer,
+}
+
+/// A component that makes a background entity scroll at a fraction of the camera's
+/// movement, so it seems further away than the level. Its `Position` is where it sits
+/// while the camera is at the origin.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Parallax {
+    /// How much of the camera's movement the entity follows. 1.0 moves with the level,
+    /// and 0.0 stays fixed on screen as if infinitely far away.
+    pub factor: f32,
+    /// Stretch the quad across the whole view horizontally, repeating its sprite,
+    /// instead of drawing it at its `Renderable` width.
+    pub repeat_x: bool,
+}
+
+/// A component that draws an entity's `Renderable` quad with an image from the
+/// texture atlas. The `Renderable` color tints the image.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Sprite {
+    /// The atlas region to draw, named after its image's path under `assets/images`,
+    /// e.g. `player` or `tiles/wall`.
+    pub region: String,
+    /// Repeat the image across the quad at its native size instead of stretching it.
+    pub tiled: bool,
+}
+
+impl Sprite {
+    pub fn new(region: &str) -> Self {
+        Self { region: region.to_string(), tiled: false }
+    }
+
+    pub fn tiled(region: &str) -> Self {
+        Self { region: region.to_string(), tiled: true }
+    }
+}
+
+/// Whether an animation clip starts over or holds its last frame once it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationMode {
+    Loop,
+    Once,
+}
+
+/// A single frame of an animation clip.
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    /// The atlas region shown during this frame.
+    pub region: String,
+    /// How long the frame is shown for, in seconds of game time.
+    pub duration: f32,
+    /// An event raised in `AnimationEvents` whenever this frame is reached.
+    pub event: Option<String>,
+}
+
+/// A sequence of frames played one after another.
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub frames: Vec<AnimationFrame>,
+    pub mode: AnimationMode,
+}
+
+impl AnimationClip {
+    /// A clip showing each region in turn for the same `frame_duration`.
+    pub fn new(regions: &[&str], frame_duration: f32, mode: AnimationMode) -> Self {
+        let frames = regions.iter()
+            .map(|region| AnimationFrame { region: region.to_string(), duration: frame_duration, event: None })
+            .collect();
+        Self { frames, mode }
+    }
+
+    /// Raises `event` whenever the clip reaches `frame`.
+    pub fn with_event(mut self, frame: usize, event: &str) -> Self {
+        if let Some(frame) = self.frames.get_mut(frame) {
+            frame.event = Some(event.to_string());
+        }
+        self
+    }
+}
+
+/// A component that flips an entity's `Sprite` through the frames of its current clip.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Animation {
+    pub clips: HashMap<String, AnimationClip>,
+    /// The name of the clip being played.
+    pub current: String,
+    /// The index of the frame being shown within the current clip.
+    pub frame: usize,
+    /// How long the current frame has been shown for.
+    pub timer: f32,
+    /// Set once a one-shot clip has reached the end of its last frame.
+    pub finished: bool,
+    /// Set when a frame is reached but its event has not been raised yet.
+    pub frame_entered: bool,
+}
+
+impl Animation {
+    /// An animation that starts out playing the clip named `initial`.
+    pub fn new(clips: Vec<(&str, AnimationClip)>, initial: &str) -> Self {
+        Self {
+            clips: clips.into_iter().map(|(name, clip)| (name.to_string(), clip)).collect(),
+            current: initial.to_string(),
+            frame: 0,
+            timer: 0.0,
+            finished: false,
+            frame_entered: true,
+        }
+    }
+
+    /// Switches to the clip named `name` and plays it from the start. Asking for the
+    /// clip that is already playing, or for a clip that does not exist, does nothing.
+    pub fn play(&mut self, name: &str) {
+        if self.current == name || !self.clips.contains_key(name) {
+            return;
+        }
+        self.current = name.to_string();
+        self.frame = 0;
+        self.timer = 0.0;
+        self.finished = false;
+        self.frame_entered = true;
+    }
+
+    /// The frame being shown, if the current clip exists and has any frames.
+    pub fn current_frame(&self) -> Option<&AnimationFrame> {
+        self.clips.get(&self.current).and_then(|clip| clip.frames.get(self.frame))
+    }
+}
+
+/// How a light is blocked by the level's static geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShadowMode {
+    /// The light shines through everything.
+    None,
+    /// Sharp-edged shadows, as if the light were a single point.
+    Hard,
+    /// Shadows with a penumbra, as if the light were a disc of `Light::source_radius`.
+    #[default]
+    Soft,
+}
+
+impl ShadowMode {
+    /// Looks up a shadow mode by the name used in level files.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "hard" => Some(Self::Hard),
+            "soft" => Some(Self::Soft),
+            _ => None,
+        }
+    }
+}
+
+/// A component that makes an entity a point light, shining from its `Position`.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Light {
+    pub color: [f32; 3],
+    /// Brightness at the center. The light fades out to nothing at `radius`.
+    pub intensity: f32,
+    pub radius: f32,
+    pub shadows: ShadowMode,
+    /// The size of the glowing body itself. Larger sources cast softer shadows.
+    pub source_radius: f32,
+}
+
+impl Light {
+    pub fn new(color: [f32; 3], intensity: f32, radius: f32, shadows: ShadowMode) -> Self {
+        Self { color, intensity, radius, shadows, source_radius: 8.0 }
+    }
+}
+
+/// How the particles of one emitter or burst look and move over their lives.
+#[derive(Debug, Clone, Copy)]
+pub struct ParticleSettings {
+    /// The range each particle's lifetime is picked from, in seconds.
+    pub lifetime: (f32, f32),
+    /// The range each particle's starting speed is picked from.
+    pub speed: (f32, f32),
+    /// The direction particles are thrown in, in radians counter-clockwise from +x.
+    pub direction: f32,
+    /// How far either side of `direction` a particle may be thrown, in radians.
+    /// `PI` throws particles in every direction.
+    pub spread: f32,
+    /// How strongly the world's gravity pulls on the particles. 0.0 lets them float.
+    pub gravity_scale: f32,
+    /// The color at birth, faded towards `color_end` over the particle's life.
+    pub color_start: [f32; 4],
+    pub color_end: [f32; 4],
+    /// The size at birth, grown or shrunk towards `size_end` over the particle's life.
+    pub size_start: f32,
+    pub size_end: f32,
+    pub layer: RenderLayer,
+}
+
+/// The game's stock particle effects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleEffect {
+    /// Kicked up by landing.
+    Dust,
+    /// A little dust kicked up by each step.
+    Footstep,
+    /// A puff under the player's feet as they jump.
+    JumpPuff,
+    /// Thrown off wherever the hook strikes.
+    Sparks,
+    /// Bits of rope or anything else that breaks apart.
+    Debris,
+    /// Rising slowly from a fire.
+    Embers,
+}
+
+impl ParticleEffect {
+    /// Looks up an effect by the name used in level files.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dust" => Some(Self::Dust),
+            "footstep" => Some(Self::Footstep),
+            "jump_puff" => Some(Self::JumpPuff),
+            "sparks" => Some(Self::Sparks),
+            "debris" => Some(Self::Debris),
+            "embers" => Some(Self::Embers),
+            _ => None,
+        }
+    }
+
+    /// How many particles one burst of the effect spawns.
+    pub fn burst_count(self) -> u32 {
+        match self {
+            Self::Dust => 14,
+            Self::Footstep => 4,
+            Self::JumpPuff => 8,
+            Self::Sparks => 18,
+            Self::Debris => 12,
+            Self::Embers => 6,
+        }
+    }
+
+    /// The effect's settings, throwing particles towards `direction`.
+    pub fn settings(self, direction: f32) -> ParticleSettings {
+        use std::f32::consts::{FRAC_PI_2, PI};
+        match self {
+            Self::Dust => ParticleSettings {
+                lifetime: (0.3, 0.6), speed: (40.0, 140.0), direction: FRAC_PI_2, spread: 1.3,
+                gravity_scale: 0.15,
+                color_start: [0.75, 0.7, 0.6, 0.8], color_end: [0.75, 0.7, 0.6, 0.0],
+                size_start: 4.0, size_end: 9.0, layer: RenderLayer::Foreground,
+            },
+            Self::Footstep => ParticleSettings {
+                lifetime: (0.2, 0.4), speed: (20.0, 60.0), direction: FRAC_PI_2, spread: 1.2,
+                gravity_scale: 0.15,
+                color_start: [0.75, 0.7, 0.6, 0.6], color_end: [0.75, 0.7, 0.6, 0.0],
+                size_start: 3.0, size_end: 6.0, layer: RenderLayer::Foreground,
+            },
+            Self::JumpPuff => ParticleSettings {
+                lifetime: (0.2, 0.35), speed: (30.0, 90.0), direction: -FRAC_PI_2, spread: 1.2,
+                gravity_scale: 0.0,
+                color_start: [0.85, 0.85, 0.85, 0.6], color_end: [0.85, 0.85, 0.85, 0.0],
+                size_start: 3.0, size_end: 7.0, layer: RenderLayer::Foreground,
+            },
+            Self::Sparks => ParticleSettings {
+                lifetime: (0.15, 0.4), speed: (150.0, 420.0), direction, spread: 1.0,
+                gravity_scale: 0.6,
+                color_start: [1.0, 0.9, 0.5, 1.0], color_end: [1.0, 0.3, 0.05, 0.0],
+                size_start: 3.0, size_end: 1.0, layer: RenderLayer::Foreground,
+            },
+            Self::Debris => ParticleSettings {
+                lifetime: (0.4, 0.9), speed: (60.0, 220.0), direction, spread: PI,
+                gravity_scale: 1.0,
+                color_start: [0.6, 0.6, 0.65, 1.0], color_end: [0.4, 0.4, 0.45, 0.0],
+                size_start: 3.0, size_end: 2.0, layer: RenderLayer::Props,
+            },
+            Self::Embers => ParticleSettings {
+                lifetime: (0.8, 1.6), speed: (20.0, 60.0), direction: FRAC_PI_2, spread: 0.5,
+                gravity_scale: -0.03,
+                color_start: [1.0, 0.7, 0.2, 1.0], color_end: [1.0, 0.2, 0.0, 0.0],
+                size_start: 3.0, size_end: 1.0, layer: RenderLayer::Foreground,
+            },
+        }
+    }
+}
+
+/// A component that spawns particles at the entity's `Position`, either steadily or
+/// in bursts whenever it is triggered.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct ParticleEmitter {
+    pub settings: ParticleSettings,
+    /// Particles spawned per second. 0.0 spawns only in bursts.
+    pub rate: f32,
+    /// How many particles each burst spawns.
+    pub burst_count: u32,
+    /// Bursts requested since the emitter last ran.
+    pub pending_bursts: u32,
+    /// Time carried over towards the next steadily spawned particle.
+    pub spawn_timer: f32,
+}
+
+impl ParticleEmitter {
+    /// An emitter that spawns `rate` particles of `effect` per second.
+    pub fn continuous(effect: ParticleEffect, rate: f32) -> Self {
+        Self { settings: effect.settings(0.0), rate, burst_count: effect.burst_count(), pending_bursts: 0, spawn_timer: 0.0 }
+    }
+
+    /// An emitter that stays quiet until it is triggered.
+    pub fn burst(effect: ParticleEffect) -> Self {
+        Self::continuous(effect, 0.0)
+    }
+
+    /// Requests a burst on the emitter's next update.
+    pub fn trigger(&mut self) {
+        self.pending_bursts += 1;
+    }
 }
 
 /// A component that holds handles to the entity's physics bodies in the rapier2d world.
@@ -29,15 +413,110 @@ pub struct PhysicsBody {
     pub collider_handle: ColliderHandle,
 }
 
-/// A marker component to identify the player entity.
+/// Collision group for solid level geometry.
+pub const GROUP_LEVEL: Group = Group::GROUP_1;
+/// Collision group for surfaces the hookshot can stick to.
+pub const GROUP_HOOKABLE: Group = Group::GROUP_2;
+/// Collision group for platforms the player can jump up through.
+pub const GROUP_ONE_WAY: Group = Group::GROUP_3;
+/// Collision group for the player.
+pub const GROUP_PLAYER: Group = Group::GROUP_4;
+/// Collision group for the links of the hookshot chain.
+pub const GROUP_CHAIN: Group = Group::GROUP_5;
+/// Collision group used by the hookshot's and the rope's ray casts.
+pub const GROUP_HOOKSHOT_RAY: Group = Group::GROUP_6;
+/// Collision group for loose dynamic objects such as crates.
+pub const GROUP_PROP: Group = Group::GROUP_7;
+
+/// The player collides with solid geometry and props only. One-way platforms are handled
+/// by the character controller, so the player swings straight through them.
+pub const PLAYER_GROUPS: InteractionGroups = InteractionGroups::new(GROUP_PLAYER, GROUP_LEVEL.union(GROUP_PROP));
+/// Chain links drape over the level but never tangle with each other or the player.
+pub const CHAIN_GROUPS: InteractionGroups = InteractionGroups::new(GROUP_CHAIN, GROUP_LEVEL.union(GROUP_PROP));
+/// Props collide with everything, and the hook can always stick to them.
+pub const PROP_GROUPS: InteractionGroups = InteractionGroups::new(GROUP_PROP.union(GROUP_HOOKABLE), Group::ALL);
+
+/// What a piece of level geometry is made of. This decides how the player and the
+/// hookshot interact with it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[storage(VecStorage)]
+pub enum SurfaceMaterial {
+    /// Plain solid ground the hook can bite into.
+    #[default]
+    Stone,
+    /// A dedicated grapple surface the hook can bite into.
+    Grapple,
+    /// Solid, but the hook passes straight through it.
+    Glass,
+    /// Solid and slippery, and the hook passes straight through it.
+    Ice,
+    /// Only solid from above. The player can jump up through it and the hook passes through it.
+    OneWay,
+}
+
+impl SurfaceMaterial {
+    /// Parses a material name as written in level files.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "stone" => Some(Self::Stone),
+            "grapple" => Some(Self::Grapple),
+            "glass" => Some(Self::Glass),
+            "ice" => Some(Self::Ice),
+            "one_way" => Some(Self::OneWay),
+            _ => None,
+        }
+    }
+
+    pub fn is_hookable(self) -> bool {
+        matches!(self, Self::Stone | Self::Grapple)
+    }
+
+    /// The collision groups for a collider made of this material.
+    pub fn collision_groups(self) -> InteractionGroups {
+        let mut memberships = if self == Self::OneWay { GROUP_ONE_WAY } else { GROUP_LEVEL };
+        if self.is_hookable() {
+            memberships |= GROUP_HOOKABLE;
+        }
+        InteractionGroups::new(memberships, Group::ALL)
+    }
+
+    pub fn friction(self) -> f32 {
+        match self {
+            Self::Ice => 0.0,
+            _ => 0.5,
+        }
+    }
+
+    /// The color a wall made of this material is drawn with.
+    pub fn color(self) -> [f32; 4] {
+        match self {
+            Self::Stone => [0.2, 0.2, 0.2, 1.0],
+            Self::Grapple => [0.45, 0.35, 0.15, 1.0],
+            Self::Glass => [0.6, 0.8, 0.9, 0.35],
+            Self::Ice => [0.7, 0.9, 1.0, 1.0],
+            Self::OneWay => [0.35, 0.25, 0.2, 1.0],
+        }
+    }
+}
+
+/// A marker component to identify loose dynamic objects, such as crates, that the
+/// hookshot can drag around.
 #[derive(Component, Debug, Default)]
 #[storage(NullStorage)]
-pub struct Player;
+pub struct Prop;
 
-/// A marker component to track if the player is on the ground.
+/// A marker component to identify the player entity.
 #[derive(Component, Debug, Default)]
 #[storage(NullStorage)]
-pub struct Grounded;
+pub struct Player;
+
+/// A component to track if the player is on the ground, and what kind of ground.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Grounded {
+    /// The friction of the collider under the player's feet.
+    pub friction: f32,
+}
 
 /// **NEW DEFINITION**
 /// This component now holds the actual Rapier KinematicCharacterController
@@ -48,3 +527,115 @@ pub struct CharacterController {
     pub controller: KinematicCharacterController,
     pub velocity: Vector2<f32>,
 }
+
+/// A corner of level geometry that the hookshot rope is currently bent around.
+#[derive(Debug, Clone, Copy)]
+pub struct WrapPoint {
+    pub point: Vector2<f32>,
+    /// The fixed body the rope is tied to while this is the innermost wrap point.
+    pub body: RigidBodyHandle,
+    /// Which way the rope bends around the corner. The wrap comes undone once the
+    /// rope bends the other way.
+    pub winding: f32,
+}
+
+/// Present on the player while it hangs from the hookshot. The player's rigid body
+/// is dynamic for as long as this component exists, and kinematic otherwise.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Swinging {
+    /// The point where the hook struck, in world space.
+    pub anchor_point: Vector2<f32>,
+    /// The body the hook is stuck in. This is a fixed body placed at the impact point
+    /// when the hook strikes the level, or the object itself when it strikes a heavy prop.
+    pub anchor_body: RigidBodyHandle,
+    /// The point where the hook struck, in `anchor_body`'s local space.
+    pub anchor_local_point: Vector2<f32>,
+    /// Whether `anchor_body` was created for the hook and must be removed when it lets go.
+    pub owns_anchor_body: bool,
+    /// The corners the rope is wrapped around, in order from the hook towards the player.
+    pub wrap_points: Vec<WrapPoint>,
+    /// The rope joint between the player's body and the active anchor, which is the
+    /// last wrap point if there is one and `anchor_body` otherwise.
+    pub rope_joint: ImpulseJointHandle,
+    /// The chain link entities hanging between the player and the active anchor, in
+    /// order from the player outwards, along with their rigid bodies.
+    pub chain_links: Vec<(Entity, RigidBodyHandle)>,
+    /// The revolute joints tying the chain together. There is one more joint than
+    /// there are links, since the chain is tied to the player and the anchor at either end.
+    pub chain_joints: Vec<ImpulseJointHandle>,
+    /// Purely visual chain link entities laid along the wrapped part of the rope.
+    pub wrapped_links: Vec<Entity>,
+}
+
+impl Swinging {
+    /// The point the free end of the rope currently pivots around.
+    pub fn active_anchor_point(&self) -> Vector2<f32> {
+        self.wrap_points.last().map_or(self.anchor_point, |wrap| wrap.point)
+    }
+
+    /// The body the rope joint is currently tied to.
+    pub fn active_anchor_body(&self) -> RigidBodyHandle {
+        self.wrap_points.last().map_or(self.anchor_body, |wrap| wrap.body)
+    }
+
+    /// Where on the active anchor body the rope is tied, in that body's local space.
+    pub fn active_anchor_local_point(&self) -> Vector2<f32> {
+        if self.wrap_points.is_empty() { self.anchor_local_point } else { Vector2::zeros() }
+    }
+
+    /// The length of rope laid along the wrapped corners, which cannot swing.
+    pub fn wrapped_length(&self) -> f32 {
+        let mut length = 0.0;
+        let mut previous = self.anchor_point;
+        for wrap in &self.wrap_points {
+            length += (wrap.point - previous).norm();
+            previous = wrap.point;
+        }
+        length
+    }
+}
+
+/// A marker component to identify the links of the hookshot chain.
+#[derive(Component, Debug, Default)]
+#[storage(NullStorage)]
+pub struct ChainLink;
+
+/// Which way the hookshot rope is currently being reeled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReelState {
+    #[default]
+    Idle,
+    ReelingIn,
+    ReelingOut,
+}
+
+/// The player's hookshot: its tuning and the current length of its rope.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct Hookshot {
+    /// The furthest distance the hook can travel before it falls short.
+    pub range: f32,
+    /// The current rope length. Only meaningful while the player is `Swinging`.
+    pub rope_length: f32,
+    /// The shortest the rope can be reeled in to.
+    pub min_length: f32,
+    /// The longest the rope can be reeled out to.
+    pub max_length: f32,
+    /// How fast the rope is reeled in or out, in units per second.
+    pub reel_speed: f32,
+    pub reel_state: ReelState,
+}
+
+impl Default for Hookshot {
+    fn default() -> Self {
+        Self {
+            range: 450.0,
+            rope_length: 0.0,
+            min_length: 30.0,
+            max_length: 450.0,
+            reel_speed: 250.0,
+            reel_state: ReelState::Idle,
+        }
+    }
+}
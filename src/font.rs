@@ -0,0 +1,45 @@
+// src/font.rs
+
+use crate::atlas::AtlasRegion;
+
+/// The atlas region holding the font's glyph sheet.
+///
+/// The sheet is printable ASCII, from `FIRST_CHAR` onwards, laid out left to right and
+/// top to bottom in a grid of equally sized cells. It was baked from DejaVu Sans Mono
+/// at 10x20 pixels per cell; text drawn at multiples of 20 pixels stays crisp.
+pub const FONT_REGION: &str = "font";
+
+/// The character in the sheet's first cell.
+const FIRST_CHAR: char = ' ';
+/// The character in the sheet's last cell.
+const LAST_CHAR: char = '~';
+/// The character drawn in place of any the sheet does not have.
+const FALLBACK_CHAR: char = '?';
+/// How many cells make up each row of the sheet.
+const COLUMNS: u32 = 16;
+/// How many rows of cells the sheet has.
+const ROWS: u32 = 6;
+/// A glyph cell's width as a fraction of its height. The font is monospaced, so this
+/// is also how far each character advances.
+pub const GLYPH_ASPECT: f32 = 0.5;
+
+/// The part of the font's `region` that holds the glyph for `c`, as `[u, v, width, height]`.
+pub fn glyph_uv_rect(region: &AtlasRegion, c: char) -> [f32; 4] {
+    let c = if (FIRST_CHAR..=LAST_CHAR).contains(&c) { c } else { FALLBACK_CHAR };
+    let index = c as u32 - FIRST_CHAR as u32;
+    let cell = [region.uv_size[0] / COLUMNS as f32, region.uv_size[1] / ROWS as f32];
+    [
+        region.uv_min[0] + (index % COLUMNS) as f32 * cell[0],
+        region.uv_min[1] + (index / COLUMNS) as f32 * cell[1],
+        cell[0],
+        cell[1],
+    ]
+}
+
+/// The width and height of `text` drawn `size` pixels tall, in pixels. Every `\n`
+/// starts a new line.
+pub fn measure_text(size: f32, text: &str) -> [f32; 2] {
+    let columns = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+    let lines = text.lines().count().max(1);
+    [columns as f32 * size * GLYPH_ASPECT, lines as f32 * size]
+}
@@ -0,0 +1,377 @@
+// src/level.rs
+
+use std::path::Path;
+use rapier2d::na::Vector2;
+use crate::components::{ParticleEffect, RenderLayer, ShadowMode, SurfaceMaterial};
+
+/// The level loaded when the game starts, resolved through `assets::path`.
+pub const FIRST_LEVEL: &str = "assets/levels/level_01.txt";
+
+/// A single wall as authored in a level file.
+#[derive(Debug, Clone, Copy)]
+pub struct WallDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub material: SurfaceMaterial,
+}
+
+/// A loose dynamic crate as authored in a level file.
+#[derive(Debug, Clone, Copy)]
+pub struct PropDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub density: f32,
+}
+
+/// A purely visual image placed in the level, with no collision.
+#[derive(Debug, Clone)]
+pub struct DecorDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub layer: RenderLayer,
+    /// The atlas region to draw, e.g. `decor/grass`.
+    pub image: String,
+}
+
+/// A point light as authored in a level file.
+#[derive(Debug, Clone, Copy)]
+pub struct LightDef {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub shadows: ShadowMode,
+}
+
+/// A particle emitter that runs steadily, as authored in a level file.
+#[derive(Debug, Clone, Copy)]
+pub struct EmitterDef {
+    pub x: f32,
+    pub y: f32,
+    pub effect: ParticleEffect,
+    /// Particles spawned per second.
+    pub rate: f32,
+}
+
+/// What a parallax layer is drawn with.
+#[derive(Debug, Clone)]
+pub enum ParallaxFill {
+    /// An image from the atlas, repeated at its native size.
+    Image(String),
+    Color([f32; 4]),
+}
+
+/// A background layer that scrolls at a fraction of the camera's movement.
+#[derive(Debug, Clone)]
+pub struct ParallaxDef {
+    /// How much of the camera's movement the layer follows, from 0.0 to 1.0.
+    pub factor: f32,
+    pub x: f32,
+    pub y: f32,
+    /// The layer's width, or `None` for a strip that repeats across the whole view.
+    pub width: Option<f32>,
+    pub height: f32,
+    pub fill: ParallaxFill,
+}
+
+/// Everything needed to build a level, as read from a level file.
+#[derive(Debug)]
+pub struct LevelData {
+    pub player_spawn: Vector2<f32>,
+    /// The `(min, max)` corners of the area the camera may show.
+    pub bounds: Option<(Vector2<f32>, Vector2<f32>)>,
+    pub walls: Vec<WallDef>,
+    pub props: Vec<PropDef>,
+    pub decor: Vec<DecorDef>,
+    /// Listed from the furthest layer to the nearest.
+    pub parallax: Vec<ParallaxDef>,
+    pub lights: Vec<LightDef>,
+    pub emitters: Vec<EmitterDef>,
+    /// How dark the level is where no light reaches, from 0.0 to 1.0.
+    pub darkness: f32,
+}
+
+impl LevelData {
+    /// Reads and parses the level file at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&source).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parses a level from the line-based level format. See `assets/levels/level_01.txt`.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut level = LevelData { player_spawn: Vector2::zeros(), bounds: None, walls: Vec::new(), props: Vec::new(), decor: Vec::new(),
+            parallax: Vec::new(), lights: Vec::new(), emitters: Vec::new(), darkness: 0.0 };
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let kind = fields.next().unwrap_or_default();
+            let args: Vec<&str> = fields.collect();
+            let number = |i: usize| -> Result<f32, String> {
+                let field = args.get(i).ok_or_else(|| format!("line {}: `{}` is missing argument {}", line_number, kind, i + 1))?;
+                field.parse().map_err(|_| format!("line {}: `{}` is not a number", line_number, field))
+            };
+
+            match kind {
+                "player" => {
+                    level.player_spawn = Vector2::new(number(0)?, number(1)?);
+                }
+                "bounds" => {
+                    level.bounds = Some((Vector2::new(number(0)?, number(1)?), Vector2::new(number(2)?, number(3)?)));
+                }
+                "wall" => {
+                    let material = match args.get(4) {
+                        Some(name) => SurfaceMaterial::from_name(name)
+                            .ok_or_else(|| format!("line {}: unknown material `{}`", line_number, name))?,
+                        None => SurfaceMaterial::default(),
+                    };
+                    level.walls.push(WallDef {
+                        x: number(0)?,
+                        y: number(1)?,
+                        width: number(2)?,
+                        height: number(3)?,
+                        material,
+                    });
+                }
+                "crate" => {
+                    let density = if args.len() > 4 { number(4)? } else { 1.0 };
+                    level.props.push(PropDef {
+                        x: number(0)?,
+                        y: number(1)?,
+                        width: number(2)?,
+                        height: number(3)?,
+                        density,
+                    });
+                }
+                "decor" => {
+                    let layer_name = args.get(4).ok_or_else(|| format!("line {}: `decor` is missing its layer", line_number))?;
+                    let layer = RenderLayer::from_name(layer_name)
+                        .ok_or_else(|| format!("line {}: unknown layer `{}`", line_number, layer_name))?;
+                    let image = args.get(5).ok_or_else(|| format!("line {}: `decor` is missing its image", line_number))?;
+                    level.decor.push(DecorDef {
+                        x: number(0)?,
+                        y: number(1)?,
+                        width: number(2)?,
+                        height: number(3)?,
+                        layer,
+                        image: image.to_string(),
+                    });
+                }
+                "darkness" => {
+                    level.darkness = number(0)?;
+                }
+                "light" => {
+                    let color = args.get(3)
+                        .ok_or_else(|| format!("line {}: `light` is missing its color", line_number))?;
+                    let color = parse_color(color).ok_or_else(|| format!("line {}: `{}` is not a valid color", line_number, color))?;
+                    let shadows = match args.get(5) {
+                        Some(name) => ShadowMode::from_name(name)
+                            .ok_or_else(|| format!("line {}: unknown shadow mode `{}`", line_number, name))?,
+                        None => ShadowMode::default(),
+                    };
+                    level.lights.push(LightDef {
+                        x: number(0)?,
+                        y: number(1)?,
+                        radius: number(2)?,
+                        color: [color[0], color[1], color[2]],
+                        intensity: if args.len() > 4 { number(4)? } else { 1.0 },
+                        shadows,
+                    });
+                }
+                "emitter" => {
+                    let name = args.get(2).ok_or_else(|| format!("line {}: `emitter` is missing its effect", line_number))?;
+                    let effect = ParticleEffect::from_name(name)
+                        .ok_or_else(|| format!("line {}: unknown particle effect `{}`", line_number, name))?;
+                    level.emitters.push(EmitterDef { x: number(0)?, y: number(1)?, effect, rate: number(3)? });
+                }
+                "parallax" | "parallax_quad" => {
+                    // A strip spans the whole view, so it has neither an x nor a width.
+                    let strip = kind == "parallax";
+                    let fill_index = if strip { 3 } else { 5 };
+                    let fill = args.get(fill_index)
+                        .ok_or_else(|| format!("line {}: `{}` is missing its image or color", line_number, kind))?;
+                    let fill = parse_fill(fill).ok_or_else(|| format!("line {}: `{}` is not a valid color", line_number, fill))?;
+                    level.parallax.push(if strip {
+                        ParallaxDef { factor: number(0)?, x: 0.0, y: number(1)?, width: None, height: number(2)?, fill }
+                    } else {
+                        ParallaxDef {
+                            factor: number(0)?,
+                            x: number(1)?,
+                            y: number(2)?,
+                            width: Some(number(3)?),
+                            height: number(4)?,
+                            fill,
+                        }
+                    });
+                }
+                _ => return Err(format!("line {}: unknown entry `{}`", line_number, kind)),
+            }
+        }
+
+        Ok(level)
+    }
+}
+
+/// Reads a parallax fill: either a `#rrggbb` color or the name of an image.
+fn parse_fill(field: &str) -> Option<ParallaxFill> {
+    if field.starts_with('#') {
+        parse_color(field).map(ParallaxFill::Color)
+    } else {
+        Some(ParallaxFill::Image(field.to_string()))
+    }
+}
+
+/// Reads a `#rrggbb` color.
+fn parse_color(field: &str) -> Option<[f32; 4]> {
+    let hex = field.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok().map(|c| c as f32 / 255.0);
+    Some([channel(0)?, channel(2)?, channel(4)?, 1.0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a level that is expected to be rejected and returns its error.
+    fn parse_error(source: &str) -> String {
+        LevelData::parse(source).expect_err("level should not parse")
+    }
+
+    #[test]
+    fn parses_the_player_and_walls() {
+        let level = LevelData::parse("
+            # A comment, then a blank line.
+
+            player 10 20
+            wall 0 -40 200 20
+            wall 50 0 20 20 ice
+        ").unwrap();
+
+        assert_eq!(level.player_spawn, Vector2::new(10.0, 20.0));
+        assert_eq!(level.walls.len(), 2);
+        let wall = level.walls[0];
+        assert_eq!((wall.x, wall.y, wall.width, wall.height), (0.0, -40.0, 200.0, 20.0));
+        assert_eq!(wall.material, SurfaceMaterial::Stone);
+        assert_eq!(level.walls[1].material, SurfaceMaterial::Ice);
+    }
+
+    #[test]
+    fn empty_level_uses_defaults() {
+        let level = LevelData::parse("").unwrap();
+        assert_eq!(level.player_spawn, Vector2::zeros());
+        assert!(level.walls.is_empty());
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        assert_eq!(parse_error("player 10"), "line 1: `player` is missing argument 2");
+        assert_eq!(parse_error("\n\nwall 0 zero 10 10"), "line 3: `zero` is not a number");
+        assert_eq!(parse_error("wall 0 0 10 10 lava"), "line 1: unknown material `lava`");
+        assert_eq!(parse_error("spawn 0 0"), "line 1: unknown entry `spawn`");
+    }
+
+    #[test]
+    fn load_reports_the_path() {
+        let error = LevelData::load(Path::new("no/such/level.txt")).expect_err("level should not load");
+        assert!(error.starts_with("no/such/level.txt: "), "{}", error);
+    }
+
+    #[test]
+    fn parses_crates() {
+        let level = LevelData::parse("crate 5 6 10 12\ncrate 5 6 10 12 0.5").unwrap();
+        let crate_def = level.props[0];
+        assert_eq!((crate_def.x, crate_def.y, crate_def.width, crate_def.height), (5.0, 6.0, 10.0, 12.0));
+        assert_eq!(crate_def.density, 1.0);
+        assert_eq!(level.props[1].density, 0.5);
+        assert_eq!(parse_error("crate 0 0 10 10 heavy"), "line 1: `heavy` is not a number");
+    }
+
+    #[test]
+    fn parses_bounds() {
+        let level = LevelData::parse("bounds -100 -50 100 50").unwrap();
+        assert_eq!(level.bounds, Some((Vector2::new(-100.0, -50.0), Vector2::new(100.0, 50.0))));
+        assert!(LevelData::parse("").unwrap().bounds.is_none());
+        assert_eq!(parse_error("bounds 0 0 10"), "line 1: `bounds` is missing argument 4");
+    }
+
+    #[test]
+    fn parses_decor() {
+        let level = LevelData::parse("decor 1 2 32 16 foreground decor/grass").unwrap();
+        let decor = &level.decor[0];
+        assert_eq!((decor.x, decor.y, decor.width, decor.height), (1.0, 2.0, 32.0, 16.0));
+        assert_eq!(decor.layer, RenderLayer::Foreground);
+        assert_eq!(decor.image, "decor/grass");
+
+        assert_eq!(parse_error("decor 0 0 1 1"), "line 1: `decor` is missing its layer");
+        assert_eq!(parse_error("decor 0 0 1 1 sky decor/grass"), "line 1: unknown layer `sky`");
+        assert_eq!(parse_error("decor 0 0 1 1 ui decor/grass"), "line 1: unknown layer `ui`");
+        assert_eq!(parse_error("decor 0 0 1 1 level"), "line 1: `decor` is missing its image");
+    }
+
+    #[test]
+    fn parses_parallax_layers() {
+        let level = LevelData::parse("parallax 0.2 10 40 #000080\nparallax_quad 0.5 1 2 3 4 backgrounds/hills").unwrap();
+        let strip = &level.parallax[0];
+        assert_eq!((strip.factor, strip.y, strip.width, strip.height), (0.2, 10.0, None, 40.0));
+        assert!(matches!(strip.fill, ParallaxFill::Color([0.0, 0.0, b, 1.0]) if b == 128.0 / 255.0));
+        let quad = &level.parallax[1];
+        assert_eq!((quad.factor, quad.x, quad.y, quad.width, quad.height), (0.5, 1.0, 2.0, Some(3.0), 4.0));
+        assert!(matches!(&quad.fill, ParallaxFill::Image(image) if image == "backgrounds/hills"));
+
+        assert_eq!(parse_error("parallax 0.5 0 10"), "line 1: `parallax` is missing its image or color");
+        assert_eq!(parse_error("parallax_quad 0.5 0 0 10 10"), "line 1: `parallax_quad` is missing its image or color");
+        assert_eq!(parse_error("parallax 0.5 0 10 #zzzzzz"), "line 1: `#zzzzzz` is not a valid color");
+    }
+
+    #[test]
+    fn parses_lights_and_darkness() {
+        let level = LevelData::parse("
+            darkness 0.75
+            light 1 2 30 #ff8000
+            light 1 2 30 #ffffff 2.0 hard
+        ").unwrap();
+        assert_eq!(level.darkness, 0.75);
+        let light = level.lights[0];
+        assert_eq!((light.x, light.y, light.radius), (1.0, 2.0, 30.0));
+        assert_eq!(light.color, [1.0, 128.0 / 255.0, 0.0]);
+        assert_eq!(light.intensity, 1.0);
+        assert_eq!(light.shadows, ShadowMode::Soft);
+        assert_eq!(level.lights[1].intensity, 2.0);
+        assert_eq!(level.lights[1].shadows, ShadowMode::Hard);
+        assert_eq!(LevelData::parse("").unwrap().darkness, 0.0);
+
+        assert_eq!(parse_error("light 0 0 10"), "line 1: `light` is missing its color");
+        assert_eq!(parse_error("light 0 0 10 orange"), "line 1: `orange` is not a valid color");
+        assert_eq!(parse_error("light 0 0 10 #ff80"), "line 1: `#ff80` is not a valid color");
+        assert_eq!(parse_error("light 0 0 10 #ffffff 1.0 fuzzy"), "line 1: unknown shadow mode `fuzzy`");
+        assert_eq!(parse_error("darkness"), "line 1: `darkness` is missing argument 1");
+    }
+
+    #[test]
+    fn parses_emitters() {
+        let level = LevelData::parse("emitter 3 4 embers 12").unwrap();
+        let emitter = level.emitters[0];
+        assert_eq!((emitter.x, emitter.y), (3.0, 4.0));
+        assert_eq!(emitter.effect, ParticleEffect::Embers);
+        assert_eq!(emitter.rate, 12.0);
+
+        assert_eq!(parse_error("emitter 0 0"), "line 1: `emitter` is missing its effect");
+        assert_eq!(parse_error("emitter 0 0 smoke 5"), "line 1: unknown particle effect `smoke`");
+    }
+}
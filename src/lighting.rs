@@ -0,0 +1,324 @@
+// src/lighting.rs
+
+use crate::resources::{LightData, LightDraw, LightVertex};
+
+/// The lightmap holds more than 1.0 where lights overlap, so it uses a float format.
+const LIGHTMAP_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+const STENCIL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24PlusStencil8;
+/// How many light samples fit in one pass. Each sample marks its shadows with its own
+/// stencil value, so the stencil buffer must be cleared once the values run out.
+const SAMPLES_PER_PASS: usize = u8::MAX as usize;
+/// How many lighting vertices the vertex buffer starts out with room for.
+const INITIAL_VERTEX_CAPACITY: usize = 4096;
+
+/// Renders the scene's lights into an offscreen lightmap, then multiplies the
+/// lightmap over the finished scene.
+///
+/// Each light sample first draws its shadow volumes into the stencil buffer, marking
+/// them with the sample's own stencil value, then draws its light everywhere that value
+/// is missing. Lights add up on top of the level's ambient light.
+pub struct LightingRenderer {
+    shadow_pipeline: wgpu::RenderPipeline,
+    light_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    composite_bind_group_layout: wgpu::BindGroupLayout,
+    composite_bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    lightmap_view: wgpu::TextureView,
+    stencil_view: wgpu::TextureView,
+    vertex_buffer: wgpu::Buffer,
+    /// How many vertices `vertex_buffer` has room for.
+    vertex_capacity: usize,
+    draws: Vec<LightDraw>,
+    /// The light level where no light reaches.
+    ambient: f64,
+}
+
+impl LightingRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        target_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("lighting.wgsl"));
+
+        // --- Lightmap pipelines ---
+        let lightmap_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Lightmap Pipeline Layout"),
+            bind_group_layouts: &[camera_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let lightmap_pipeline = |label, entry_point, blend, write_mask, stencil| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&lightmap_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_light",
+                    buffers: &[LightVertex::desc()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState { format: LIGHTMAP_FORMAT, blend, write_mask })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: STENCIL_FORMAT,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil,
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+        let stencil = |compare, pass_op, write_mask| {
+            let face = wgpu::StencilFaceState { compare, fail_op: wgpu::StencilOperation::Keep, depth_fail_op: wgpu::StencilOperation::Keep, pass_op };
+            wgpu::StencilState { front: face, back: face, read_mask: 0xff, write_mask }
+        };
+        // Shadow volumes stamp the sample's stencil value without touching the color.
+        let shadow_pipeline = lightmap_pipeline(
+            "Shadow Pipeline",
+            "fs_shadow",
+            None,
+            wgpu::ColorWrites::empty(),
+            stencil(wgpu::CompareFunction::Always, wgpu::StencilOperation::Replace, 0xff),
+        );
+        // Lights add up wherever the sample's shadows did not land.
+        let additive = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let light_pipeline = lightmap_pipeline(
+            "Light Pipeline",
+            "fs_light",
+            Some(wgpu::BlendState { color: additive, alpha: additive }),
+            wgpu::ColorWrites::ALL,
+            stencil(wgpu::CompareFunction::NotEqual, wgpu::StencilOperation::Keep, 0x00),
+        );
+
+        // --- Composite pipeline ---
+        let composite_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Lightmap Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let composite_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Composite Pipeline Layout"),
+            bind_group_layouts: &[&composite_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        // Multiplies the scene by the lightmap: result = lightmap * scene.
+        let multiply = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::Dst,
+                dst_factor: wgpu::BlendFactor::Zero,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::Zero,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+        };
+        let composite_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Composite Pipeline"),
+            layout: Some(&composite_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_fullscreen",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_composite",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(multiply),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Lightmap Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let (lightmap_view, stencil_view) = create_targets(device, width, height);
+        let composite_bind_group = create_composite_bind_group(device, &composite_bind_group_layout, &lightmap_view, &sampler);
+
+        Self {
+            shadow_pipeline,
+            light_pipeline,
+            composite_pipeline,
+            composite_bind_group_layout,
+            composite_bind_group,
+            sampler,
+            lightmap_view,
+            stencil_view,
+            vertex_buffer: create_vertex_buffer(device, INITIAL_VERTEX_CAPACITY),
+            vertex_capacity: INITIAL_VERTEX_CAPACITY,
+            draws: Vec::new(),
+            ambient: 1.0,
+        }
+    }
+
+    /// Recreates the lightmap to match the new size of the screen.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let (lightmap_view, stencil_view) = create_targets(device, width, height);
+        self.composite_bind_group = create_composite_bind_group(device, &self.composite_bind_group_layout, &lightmap_view, &self.sampler);
+        self.lightmap_view = lightmap_view;
+        self.stencil_view = stencil_view;
+    }
+
+    /// Uploads this frame's lighting geometry.
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, light_data: &LightData, darkness: f32) {
+        if light_data.vertices.len() > self.vertex_capacity {
+            self.vertex_capacity = light_data.vertices.len().next_power_of_two();
+            self.vertex_buffer = create_vertex_buffer(device, self.vertex_capacity);
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&light_data.vertices));
+        self.draws.clone_from(&light_data.draws);
+        self.ambient = (1.0 - darkness.clamp(0.0, 1.0)) as f64;
+    }
+
+    /// Renders the lightmap. This must happen before the scene is composited.
+    pub fn render_lightmap(&self, encoder: &mut wgpu::CommandEncoder, camera_bind_group: &wgpu::BindGroup) {
+        let ambient = wgpu::Color { r: self.ambient, g: self.ambient, b: self.ambient, a: 1.0 };
+        // Even without any lights, the lightmap still needs clearing to the ambient level.
+        let mut chunks: Vec<&[LightDraw]> = self.draws.chunks(SAMPLES_PER_PASS).collect();
+        if chunks.is_empty() {
+            chunks.push(&[]);
+        }
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Lightmap Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.lightmap_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: if index == 0 { wgpu::LoadOp::Clear(ambient) } else { wgpu::LoadOp::Load },
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.stencil_view,
+                    depth_ops: None,
+                    stencil_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_bind_group(0, camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+
+            for (sample, draw) in chunk.iter().enumerate() {
+                // Stencil value 0 is the cleared state, so samples count up from 1.
+                render_pass.set_stencil_reference(sample as u32 + 1);
+                if !draw.shadows.is_empty() {
+                    render_pass.set_pipeline(&self.shadow_pipeline);
+                    render_pass.draw(draw.shadows.clone(), 0..1);
+                }
+                render_pass.set_pipeline(&self.light_pipeline);
+                render_pass.draw(draw.light.clone(), 0..1);
+            }
+        }
+    }
+
+    /// Multiplies the lightmap over whatever `render_pass` has drawn so far.
+    pub fn composite<'p>(&'p self, render_pass: &mut wgpu::RenderPass<'p>) {
+        render_pass.set_pipeline(&self.composite_pipeline);
+        render_pass.set_bind_group(0, &self.composite_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Creates the lightmap and its stencil buffer.
+fn create_targets(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::TextureView, wgpu::TextureView) {
+    let size = wgpu::Extent3d { width: width.max(1), height: height.max(1), depth_or_array_layers: 1 };
+    let target = |label, format, usage| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        }).create_view(&wgpu::TextureViewDescriptor::default())
+    };
+    (
+        target("Lightmap", LIGHTMAP_FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING),
+        target("Lightmap Stencil", STENCIL_FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT),
+    )
+}
+
+fn create_composite_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    lightmap_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Lightmap Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(lightmap_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+/// Allocates a lighting vertex buffer with room for `capacity` vertices.
+fn create_vertex_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    let size = std::mem::size_of::<LightVertex>() * capacity;
+    log::info!("[Lighting] Allocating vertex buffer for {} vertices ({} KiB)", capacity, size / 1024);
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Light Vertex Buffer"),
+        size: size as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,73 @@
+// lighting.wgsl
+
+// The camera transform, shared with shader.wgsl.
+// It must match the CameraUniform struct in resources.rs.
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+// It must match the LightVertex struct in resources.rs.
+struct LightVertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) local: vec2<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct LightVertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_light(model: LightVertexInput) -> LightVertexOutput {
+    var out: LightVertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(model.position, 0.0, 1.0);
+    out.local = model.local;
+    out.color = model.color;
+    return out;
+}
+
+// Shadow volumes only mark the stencil buffer; their color is masked off.
+@fragment
+fn fs_shadow(in: LightVertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+
+@fragment
+fn fs_light(in: LightVertexOutput) -> @location(0) vec4<f32> {
+    // Fade smoothly from full strength at the center to nothing at the radius.
+    let falloff = clamp(1.0 - length(in.local), 0.0, 1.0);
+    return vec4<f32>(in.color.rgb * falloff * falloff, 1.0);
+}
+
+// --- Composite ---
+// Multiplies the finished lightmap over the scene.
+
+@group(0) @binding(0)
+var t_lightmap: texture_2d<f32>;
+@group(0) @binding(1)
+var s_lightmap: sampler;
+
+struct FullscreenOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// A single triangle large enough to cover the whole screen.
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> FullscreenOutput {
+    var out: FullscreenOutput;
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    return out;
+}
+
+@fragment
+fn fs_composite(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_lightmap, s_lightmap, in.uv);
+}
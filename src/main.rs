@@ -2,28 +2,57 @@
 
 use std::iter;
 use std::time::Instant;
+use wgpu::util::DeviceExt;
 use winit::{
     application::ApplicationHandler,
-    event::{WindowEvent, ElementState},
+    event::{WindowEvent, ElementState, MouseButton},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
     keyboard::KeyCode,
     keyboard::PhysicalKey,
     window::Window,
 };
-use specs::{World, WorldExt, Builder, Dispatcher, DispatcherBuilder, System, Write};
+use specs::{World, WorldExt, Builder, Entity, Dispatcher, DispatcherBuilder, RunNow, System, Write};
 use rapier2d::prelude::*;
 use rapier2d::na::Vector2;
 // **FIXED: ADDED THE MISSING IMPORT**
 use rapier2d::control::KinematicCharacterController;
-use log;
+use gilrs::{EventType, Gilrs};
 
+mod assets;
+mod atlas;
+mod audio;
+mod color;
 mod components;
+mod font;
+mod level;
+mod lighting;
+mod post;
 mod resources;
+mod settings;
 mod systems;
+mod ui;
 
 use components::*;
+// Shadows rapier's `Rotation`, which the prelude glob also brings in.
+use components::Rotation;
+use atlas::{TextureAtlas, IMAGES_DIR};
+use audio::{Audio, SOUNDS_DIR};
+use lighting::LightingRenderer;
+use post::{PostProcessor, SCENE_FORMAT};
+use level::{LevelData, WallDef, PropDef, DecorDef, EmitterDef, LightDef, ParallaxDef, ParallaxFill, FIRST_LEVEL};
 use resources::*;
-use systems::{physics::PhysicsSystem, player_control::PlayerControlSystem, rendering::RenderingSystem};
+use systems::{animation::AnimationSystem, camera::CameraSystem, debug_draw::DebugDrawSystem, footsteps::FootstepSystem, hookshot::HookshotSystem, hud::HudSystem, lighting::LightingSystem, menu::MenuSystem, particles::ParticleSystem, physics::PhysicsSystem, player_control::PlayerControlSystem, rendering::RenderingSystem, ui::UiSystem};
+
+/// The most simulation ticks a single frame may run. After a long stall (a window
+/// drag, a breakpoint) the game slows down for a moment instead of freezing while it
+/// catches up.
+const MAX_TICKS_PER_FRAME: u32 = 5;
+/// How many quads the instance buffer starts out with room for.
+const INITIAL_INSTANCE_CAPACITY: usize = 1024;
+/// The time scale used while slow motion is toggled on.
+const SLOW_MOTION_SCALE: f32 = 0.25;
+/// The color behind everything, in sRGB.
+const CLEAR_COLOR: [f32; 3] = [0.05, 0.05, 0.1];
 
 // A simple system to reset single-frame input flags
 pub struct InputResetSystem;
@@ -31,6 +60,18 @@ impl<'a> System<'a> for InputResetSystem {
     type SystemData = Write<'a, InputState>;
     fn run(&mut self, mut input_state: Self::SystemData) {
         input_state.jump_pressed = false;
+        input_state.fire_pressed = false;
+        input_state.release_pressed = false;
+    }
+}
+
+// Menus keep running while the game is paused, so their input is reset once per
+// frame rather than once per tick.
+pub struct MenuInputResetSystem;
+impl<'a> System<'a> for MenuInputResetSystem {
+    type SystemData = Write<'a, InputState>;
+    fn run(&mut self, mut input_state: Self::SystemData) {
+        input_state.menu = MenuInput::default();
     }
 }
 
@@ -48,18 +89,43 @@ struct State<'a> {
     size: winit::dpi::PhysicalSize<u32>,
     window: &'a Window,
     render_pipeline: wgpu::RenderPipeline,
-    vertex_buffer: wgpu::Buffer,
-    num_vertices: u32,
+    /// The present modes the surface supports.
+    present_modes: Vec<wgpu::PresentMode>,
+    /// The graphics settings as last applied and saved, to spot when the menu changes them.
+    graphics_settings: GraphicsSettings,
+    /// The unit quad every instance is drawn from.
+    quad_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    /// How many instances `instance_buffer` has room for.
+    instance_capacity: usize,
+    /// How many of the instances are the world's, drawn before lighting.
+    num_instances: u32,
+    /// How many overlay instances follow the world's, drawn after lighting.
+    num_overlay_instances: u32,
+    /// How many UI instances follow the overlay's, drawn last in screen space.
+    num_ui_instances: u32,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    /// The screen-space projection the UI layer is drawn with.
+    ui_camera_buffer: wgpu::Buffer,
+    ui_camera_bind_group: wgpu::BindGroup,
+    atlas_bind_group: wgpu::BindGroup,
+    lighting: LightingRenderer,
+    post: PostProcessor,
+    /// Gamepad input for the menus, or `None` if gamepads are unavailable.
+    gamepads: Option<Gilrs>,
     
     ecs_world: World,
     dispatcher: Dispatcher<'a, 'a>,
+    render_dispatcher: Dispatcher<'a, 'a>,
 
     last_update: Instant,
     accumulator: f32,
 }
 
 impl<'a> State<'a> {
-    async fn new(window: &'a Window) -> Self {
+    /// Sets up rendering and the game world, or reports why the level could not be loaded.
+    async fn new(window: &'a Window) -> Result<Self, String> {
         let size = window.inner_size();
 
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
@@ -75,25 +141,166 @@ impl<'a> State<'a> {
             .unwrap();
 
         let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps.formats[0];
+        // Rendering happens in linear light, so a surface that encodes to sRGB by itself
+        // is preferred. Otherwise the post-processor's final copy does the encoding.
+        let surface_format = surface_caps.formats.iter()
+            .copied()
+            .find(|format| format.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+        // The game draws every pixel itself, so nothing behind the window should show through.
+        let alpha_mode = if surface_caps.alpha_modes.contains(&wgpu::CompositeAlphaMode::Opaque) {
+            wgpu::CompositeAlphaMode::Opaque
+        } else {
+            surface_caps.alpha_modes[0]
+        };
+        let graphics_settings = settings::load();
+        let present_mode = choose_present_mode(&surface_caps.present_modes, graphics_settings.present_mode);
+        log::info!("[Render] Using surface format {:?} with present mode {:?}", surface_format, present_mode);
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: surface_caps.present_modes[0],
-            alpha_mode: surface_caps.alpha_modes[0],
+            present_mode,
+            alpha_mode,
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
         surface.configure(&device, &config);
 
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shader.wgsl"));
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shader.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(concat!(include_str!("color.wgsl"), include_str!("shader.wgsl")).into()),
+        });
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Camera Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+        });
+
+        // The UI layer ignores the camera and is drawn in window pixels instead.
+        let ui_camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("UI Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let ui_camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("UI Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: ui_camera_buffer.as_entire_binding(),
+            }],
+        });
         
+        // --- Sprite Atlas ---
+        let (texture_atlas, atlas_image) = TextureAtlas::load_dir(&assets::path(IMAGES_DIR));
+        let atlas_size = wgpu::Extent3d {
+            width: atlas_image.width(),
+            height: atlas_image.height(),
+            depth_or_array_layers: 1,
+        };
+        let atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Atlas Texture"),
+            size: atlas_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &atlas_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &atlas_image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * atlas_image.width()),
+                rows_per_image: Some(atlas_image.height()),
+            },
+            atlas_size,
+        );
+        let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Nearest filtering keeps the pixel art crisp.
+        let atlas_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Atlas Sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let atlas_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Atlas Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let atlas_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Atlas Bind Group"),
+            layout: &atlas_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&atlas_sampler),
+                },
+            ],
+        });
+
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[],
+                bind_group_layouts: &[&camera_bind_group_layout, &atlas_bind_group_layout],
                 push_constant_ranges: &[],
             });
         
@@ -103,14 +310,14 @@ impl<'a> State<'a> {
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: "vs_main",
-                buffers: &[Vertex::desc()],
+                buffers: &[Vertex::desc(), Instance::desc()],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format: SCENE_FORMAT,
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -122,40 +329,97 @@ impl<'a> State<'a> {
             multiview: None,
         });
 
-        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Vertex Buffer"),
-            size: (std::mem::size_of::<Vertex>() * 6 * 1024) as wgpu::BufferAddress,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
+        let lighting = LightingRenderer::new(&device, config.width, config.height, SCENE_FORMAT, &camera_bind_group_layout);
+        let post = PostProcessor::new(&device, &queue, &config);
+
+        let quad_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Quad Buffer"),
+            contents: bytemuck::cast_slice(&QUAD_VERTICES),
+            usage: wgpu::BufferUsages::VERTEX,
         });
+        let instance_buffer = create_instance_buffer(&device, INITIAL_INSTANCE_CAPACITY);
 
         // --- Specs ECS Setup ---
         let mut ecs_world = World::new();
         ecs_world.register::<Position>();
+        ecs_world.register::<Rotation>();
         ecs_world.register::<Renderable>();
+        ecs_world.register::<Sprite>();
+        ecs_world.register::<Parallax>();
+        ecs_world.register::<Light>();
+        ecs_world.register::<ParticleEmitter>();
         ecs_world.register::<PhysicsBody>();
         ecs_world.register::<Player>();
         ecs_world.register::<Grounded>();
         ecs_world.register::<CharacterController>();
+        ecs_world.register::<Swinging>();
+        ecs_world.register::<Hookshot>();
+        ecs_world.register::<Animation>();
+        ecs_world.register::<ChainLink>();
+        ecs_world.register::<SurfaceMaterial>();
+        ecs_world.register::<Prop>();
 
 
         ecs_world.insert(PhysicsWorld::default());
         ecs_world.insert(RenderData::default());
+        ecs_world.insert(DebugDraw::default());
+        ecs_world.insert(UiDrawList::default());
+        ecs_world.insert(Menu::default());
+        ecs_world.insert(RenderAlpha::default());
+        ecs_world.insert(texture_atlas);
+        ecs_world.insert(LightData::default());
+        ecs_world.insert(Particles::default());
+        ecs_world.insert(ParticleBursts::default());
+        ecs_world.insert(GameTime::default());
+        ecs_world.insert(Camera::default());
+        ecs_world.insert(CameraShake::default());
+        ecs_world.insert(HitEffect::default());
+        ecs_world.insert(graphics_settings);
         ecs_world.insert(InputState::default());
+        ecs_world.insert(HookshotEvents::default());
+        ecs_world.insert(AnimationEvents::default());
+        ecs_world.insert(Audio::load_dir(&assets::path(SOUNDS_DIR)));
         ecs_world.insert(ScreenDimensions { width: size.width as f32, height: size.height as f32 });
 
         let dispatcher = DispatcherBuilder::new()
-            .with(PlayerControlSystem, "player_control", &[])
+            .with(HookshotSystem, "hookshot", &[])
+            .with(PlayerControlSystem, "player_control", &["hookshot"])
             .with(PhysicsSystem, "physics_system", &["player_control"])
-            .with(RenderingSystem, "rendering_system", &["physics_system"])
+            .with(AnimationSystem, "animation_system", &["physics_system"])
+            .with(FootstepSystem, "footstep_system", &["animation_system"])
+            .with(ParticleSystem::default(), "particle_system", &["physics_system", "footstep_system"])
             .with_thread_local(InputResetSystem) 
             .build();
 
+        // Rendering runs once per frame rather than once per physics tick.
+        let render_dispatcher = DispatcherBuilder::new()
+            .with(CameraSystem, "camera_system", &[])
+            .with(RenderingSystem::default(), "rendering_system", &["camera_system"])
+            .with(LightingSystem, "lighting_system", &["camera_system"])
+            .with(DebugDrawSystem::default(), "debug_draw_system", &["camera_system"])
+            .with(HudSystem::default(), "hud_system", &[])
+            .with(MenuSystem::default(), "menu_system", &["hud_system"])
+            .with(UiSystem, "ui_system", &["hud_system", "menu_system"])
+            .with_thread_local(MenuInputResetSystem)
+            .build();
+
+        let gamepads = Gilrs::new()
+            .inspect_err(|e| log::warn!("[Input] Gamepads are unavailable: {}", e))
+            .ok();
+
         // --- Create Entities ---
-        create_level(&mut ecs_world);
-        create_player(&mut ecs_world, 0.0, 100.0);
+        let level = LevelData::load(&assets::path(FIRST_LEVEL))?;
+        create_level(&mut ecs_world, &level);
+        let player = create_player(&mut ecs_world, level.player_spawn.x, level.player_spawn.y);
+        {
+            let mut camera = ecs_world.write_resource::<Camera>();
+            camera.target = Some(player);
+            camera.bounds = level.bounds;
+            camera.position = level.player_spawn;
+            camera.focus = level.player_spawn;
+        }
 
-        Self {
+        Ok(Self {
             window,
             surface,
             device,
@@ -163,13 +427,28 @@ impl<'a> State<'a> {
             config,
             size,
             render_pipeline,
-            vertex_buffer,
-            num_vertices: 0,
+            present_modes: surface_caps.present_modes,
+            graphics_settings,
+            quad_buffer,
+            instance_buffer,
+            instance_capacity: INITIAL_INSTANCE_CAPACITY,
+            num_instances: 0,
+            num_overlay_instances: 0,
+            num_ui_instances: 0,
+            camera_buffer,
+            camera_bind_group,
+            ui_camera_buffer,
+            ui_camera_bind_group,
+            atlas_bind_group,
+            lighting,
+            post,
+            gamepads,
             ecs_world,
             dispatcher,
+            render_dispatcher,
             last_update: Instant::now(),
             accumulator: 0.0,
-        }
+        })
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -178,6 +457,8 @@ impl<'a> State<'a> {
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
+            self.lighting.resize(&self.device, new_size.width, new_size.height);
+            self.post.resize(&self.device, new_size.width, new_size.height);
             let mut screen_dim = self.ecs_world.write_resource::<ScreenDimensions>();
             screen_dim.width = new_size.width as f32;
             screen_dim.height = new_size.height as f32;
@@ -198,8 +479,26 @@ impl<'a> State<'a> {
                 let mut input_state = self.ecs_world.write_resource::<InputState>();
                 match state {
                     ElementState::Pressed => {
-                        if input_state.pressed_keys.insert(*keycode) && *keycode == KeyCode::Space {
-                            input_state.jump_pressed = true;
+                        if input_state.pressed_keys.insert(*keycode) {
+                            input_state.menu.press_key(*keycode);
+                            match keycode {
+                                KeyCode::Space => input_state.jump_pressed = true,
+                                // The menu pauses the game for as long as it is open.
+                                KeyCode::KeyP if self.ecs_world.read_resource::<Menu>().screen == MenuScreen::Closed => {
+                                    let mut time = self.ecs_world.write_resource::<GameTime>();
+                                    time.paused = !time.paused;
+                                }
+                                KeyCode::F3 => {
+                                    let mut debug_draw = self.ecs_world.write_resource::<DebugDraw>();
+                                    debug_draw.enabled = !debug_draw.enabled;
+                                }
+                                KeyCode::KeyT => {
+                                    let mut time = self.ecs_world.write_resource::<GameTime>();
+                                    let scale = if time.time_scale < 1.0 { 1.0 } else { SLOW_MOTION_SCALE };
+                                    time.set_time_scale(scale);
+                                }
+                                _ => {}
+                            }
                         }
                     }
                     ElementState::Released => {
@@ -208,42 +507,154 @@ impl<'a> State<'a> {
                 }
                 true
             }
+            WindowEvent::CursorMoved { position, .. } => {
+                let mut input_state = self.ecs_world.write_resource::<InputState>();
+                input_state.cursor_position = Vector2::new(position.x as f32, position.y as f32);
+                true
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let mut input_state = self.ecs_world.write_resource::<InputState>();
+                match state {
+                    ElementState::Pressed => {
+                        if input_state.pressed_mouse_buttons.insert(*button) {
+                            match button {
+                                MouseButton::Left => {
+                                    input_state.fire_pressed = true;
+                                    input_state.menu.click = true;
+                                }
+                                MouseButton::Right => input_state.release_pressed = true,
+                                _ => {}
+                            }
+                        }
+                    }
+                    ElementState::Released => {
+                        input_state.pressed_mouse_buttons.remove(button);
+                    }
+                }
+                true
+            }
             _ => false,
         }
     }
 
     fn update(&mut self) {
-        let dt = self.ecs_world.read_resource::<PhysicsWorld>().integration_parameters.dt;
         let now = Instant::now();
-        self.accumulator += now.duration_since(self.last_update).as_secs_f32();
+        let frame_time = now.duration_since(self.last_update).as_secs_f32();
         self.last_update = now;
 
-        while self.accumulator >= dt {
+        let (fixed_dt, paused) = {
+            let mut time = self.ecs_world.write_resource::<GameTime>();
+            time.real_dt = frame_time;
+            (time.fixed_dt, time.paused)
+        };
+
+        if paused {
+            // Don't bank time while paused, and drop any presses made in the meantime. The
+            // accumulator keeps its leftover, so the render alpha and with it the scene
+            // stay exactly where they were when the game paused.
+            InputResetSystem.run_now(&self.ecs_world);
+        } else {
+            self.accumulator += frame_time;
+        }
+
+        let mut ticks = 0;
+        while self.accumulator >= fixed_dt {
+            if ticks == MAX_TICKS_PER_FRAME {
+                log::warn!("[GameLoop] Dropping {:.3}s of simulation after falling behind", self.accumulator);
+                self.accumulator %= fixed_dt;
+                break;
+            }
+            self.ecs_world.write_resource::<GameTime>().advance();
             self.dispatcher.dispatch(&self.ecs_world);
             self.ecs_world.maintain();
-            self.accumulator -= dt;
+            self.accumulator -= fixed_dt;
+            ticks += 1;
         }
-        
+
+        self.ecs_world.write_resource::<RenderAlpha>().0 = self.accumulator / fixed_dt;
+        self.poll_gamepads();
+        self.render_dispatcher.dispatch(&self.ecs_world);
+
         let render_data = self.ecs_world.read_resource::<RenderData>();
-        self.num_vertices = render_data.0.len() as u32;
-        self.queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&render_data.0));
+        // Grow the instance buffer when the scene outgrows it. Doubling keeps the number
+        // of reallocations small as a level fills up.
+        let total = render_data.instances.len() + render_data.overlay.len() + render_data.ui.len();
+        if total > self.instance_capacity {
+            self.instance_capacity = total.next_power_of_two();
+            self.instance_buffer = create_instance_buffer(&self.device, self.instance_capacity);
+        }
+        // The overlay goes straight after the world's instances, and the UI after the
+        // overlay, all in the same buffer.
+        self.num_instances = render_data.instances.len() as u32;
+        self.num_overlay_instances = render_data.overlay.len() as u32;
+        self.num_ui_instances = render_data.ui.len() as u32;
+        let mut offset = 0;
+        for instances in [&render_data.instances, &render_data.overlay, &render_data.ui] {
+            self.queue.write_buffer(&self.instance_buffer, offset, bytemuck::cast_slice(instances));
+            offset += (std::mem::size_of::<Instance>() * instances.len()) as wgpu::BufferAddress;
+        }
+        self.lighting.prepare(
+            &self.device,
+            &self.queue,
+            &self.ecs_world.read_resource::<LightData>(),
+            self.ecs_world.read_resource::<Lighting>().darkness,
+        );
+        let graphics_settings = *self.ecs_world.read_resource::<GraphicsSettings>();
+        self.post.prepare(&self.queue, &graphics_settings, self.ecs_world.read_resource::<HitEffect>().strength);
+        if graphics_settings != self.graphics_settings {
+            if graphics_settings.present_mode != self.graphics_settings.present_mode {
+                self.config.present_mode = choose_present_mode(&self.present_modes, graphics_settings.present_mode);
+                log::info!("[Render] Switching to present mode {:?}", self.config.present_mode);
+                self.surface.configure(&self.device, &self.config);
+            }
+            self.graphics_settings = graphics_settings;
+            settings::save(&graphics_settings);
+        }
+
+        let camera_uniform = CameraUniform::new(
+            &self.ecs_world.read_resource::<Camera>(),
+            &self.ecs_world.read_resource::<ScreenDimensions>(),
+        );
+        self.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+        let ui_camera_uniform = CameraUniform::screen(&self.ecs_world.read_resource::<ScreenDimensions>());
+        self.queue.write_buffer(&self.ui_camera_buffer, 0, bytemuck::cast_slice(&[ui_camera_uniform]));
+    }
+
+    /// Feeds gamepad button presses since the last frame into the menu's input.
+    fn poll_gamepads(&mut self) {
+        let Some(gamepads) = self.gamepads.as_mut() else {
+            return;
+        };
+        let mut input_state = self.ecs_world.write_resource::<InputState>();
+        while let Some(event) = gamepads.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                input_state.menu.press_button(button);
+            }
+        }
+    }
+
+    /// Whether the player has asked to quit from the menu.
+    fn quit_requested(&self) -> bool {
+        self.ecs_world.read_resource::<Menu>().quit_requested
     }
 
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        self.lighting.render_lightmap(&mut encoder, &self.camera_bind_group);
         {
+            let clear = color::to_linear(CLEAR_COLOR);
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: self.post.scene_view(),
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.05,
-                            g: 0.05,
-                            b: 0.1,
+                            r: clear[0] as f64,
+                            g: clear[1] as f64,
+                            b: clear[2] as f64,
                             a: 1.0,
                         }),
                         store: wgpu::StoreOp::Store,
@@ -254,16 +665,80 @@ impl<'a> State<'a> {
                 timestamp_writes: None,
             });
             render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.draw(0..self.num_vertices, 0..1);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.atlas_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.quad_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+            render_pass.draw(0..QUAD_VERTICES.len() as u32, 0..self.num_instances);
+            self.lighting.composite(&mut render_pass);
         }
+        self.post.apply(&mut encoder);
+        {
+            // The overlay and the UI go on top of the post-processed image, untouched by it.
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Screen Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: self.post.output_view(),
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(1, &self.atlas_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.quad_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+
+            if self.num_overlay_instances > 0 {
+                let overlay = self.num_instances..self.num_instances + self.num_overlay_instances;
+                render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+                render_pass.draw(0..QUAD_VERTICES.len() as u32, overlay);
+            }
+
+            if self.num_ui_instances > 0 {
+                let start = self.num_instances + self.num_overlay_instances;
+                render_pass.set_bind_group(0, &self.ui_camera_bind_group, &[]);
+                render_pass.draw(0..QUAD_VERTICES.len() as u32, start..start + self.num_ui_instances);
+            }
+        }
+        self.post.present(&mut encoder, &view);
         self.queue.submit(iter::once(encoder.finish()));
         output.present();
         Ok(())
     }
 }
 
-fn create_wall(world: &mut World, x: f32, y: f32, width: f32, height: f32) {
+/// Picks the surface's present mode for the player's setting, falling back to vsync,
+/// which every surface supports, when the setting's mode isn't available.
+fn choose_present_mode(supported: &[wgpu::PresentMode], setting: PresentMode) -> wgpu::PresentMode {
+    let wanted = setting.to_wgpu();
+    if supported.contains(&wanted) {
+        wanted
+    } else {
+        log::warn!("[Render] Present mode {:?} is not supported here; falling back to vsync", wanted);
+        wgpu::PresentMode::Fifo
+    }
+}
+
+/// Allocates an instance buffer with room for `capacity` quads.
+fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    let size = std::mem::size_of::<Instance>() * capacity;
+    log::info!("[Render] Allocating instance buffer for {} quads ({} KiB)", capacity, size / 1024);
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Instance Buffer"),
+        size: size as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_wall(world: &mut World, wall: &WallDef) {
+    let WallDef { x, y, width, height, material } = *wall;
     let (rb_handle, col_handle) = {
         let mut pw = world.write_resource::<PhysicsWorld>();
         let pw = &mut *pw;
@@ -271,38 +746,128 @@ fn create_wall(world: &mut World, x: f32, y: f32, width: f32, height: f32) {
         let collider_set = &mut pw.collider_set;
 
         let rigid_body = RigidBodyBuilder::fixed().translation(vector![x, y]).build();
-        let collider = ColliderBuilder::cuboid(width / 2.0, height / 2.0).build();
+        let collider = ColliderBuilder::cuboid(width / 2.0, height / 2.0)
+            .collision_groups(material.collision_groups())
+            .friction(material.friction())
+            .build();
         let rb_handle = rigid_body_set.insert(rigid_body);
         let col_handle = collider_set.insert_with_parent(collider, rb_handle, rigid_body_set);
         (rb_handle, col_handle)
     };
     
     world.create_entity()
-        .with(Position(Vector2::new(x, y)))
-        .with(Renderable { color: [0.2, 0.2, 0.2, 1.0], width, height })
+        .with(Position::new(Vector2::new(x, y)))
+        .with(Rotation::default())
+        .with(Renderable { color: material.color(), width, height, layer: RenderLayer::Level })
+        .with(Sprite::tiled("tiles/wall"))
         .with(PhysicsBody { rigid_body_handle: rb_handle, collider_handle: col_handle })
+        .with(material)
         .build();
 }
 
-fn create_level(world: &mut World) {
-    create_wall(world, 0.0, -250.0, 500.0, 20.0);
-    create_wall(world, 200.0, -150.0, 200.0, 20.0);
-    create_wall(world, -200.0, 0.0, 200.0, 20.0);
-    create_wall(world, -200.0, -150.0, 20.0, 200.0);
+fn create_prop(world: &mut World, prop: &PropDef) {
+    let PropDef { x, y, width, height, density } = *prop;
+    let (rb_handle, col_handle) = {
+        let mut pw = world.write_resource::<PhysicsWorld>();
+        let pw = &mut *pw;
+        let rigid_body_set = &mut pw.rigid_body_set;
+        let collider_set = &mut pw.collider_set;
+
+        let rigid_body = RigidBodyBuilder::dynamic().translation(vector![x, y]).build();
+        let collider = ColliderBuilder::cuboid(width / 2.0, height / 2.0)
+            .density(density)
+            .collision_groups(PROP_GROUPS)
+            .build();
+        let rb_handle = rigid_body_set.insert(rigid_body);
+        let col_handle = collider_set.insert_with_parent(collider, rb_handle, rigid_body_set);
+        (rb_handle, col_handle)
+    };
+
+    world.create_entity()
+        .with(Position::new(Vector2::new(x, y)))
+        .with(Rotation::default())
+        .with(Renderable { color: [1.0, 1.0, 1.0, 1.0], width, height, layer: RenderLayer::Props })
+        .with(Sprite::new("crate"))
+        .with(PhysicsBody { rigid_body_handle: rb_handle, collider_handle: col_handle })
+        .with(Prop)
+        .with(ParticleEmitter::burst(ParticleEffect::Debris))
+        .build();
+}
+
+fn create_decor(world: &mut World, decor: &DecorDef) {
+    world.create_entity()
+        .with(Position::new(Vector2::new(decor.x, decor.y)))
+        .with(Renderable { color: [1.0, 1.0, 1.0, 1.0], width: decor.width, height: decor.height, layer: decor.layer })
+        .with(Sprite::tiled(&decor.image))
+        .build();
+}
+
+fn create_parallax_layer(world: &mut World, layer: &ParallaxDef) {
+    let (color, sprite) = match &layer.fill {
+        ParallaxFill::Image(image) => ([1.0, 1.0, 1.0, 1.0], Sprite::tiled(image)),
+        ParallaxFill::Color(color) => (*color, Sprite::new(atlas::WHITE_REGION)),
+    };
+    world.create_entity()
+        .with(Position::new(Vector2::new(layer.x, layer.y)))
+        .with(Renderable { color, width: layer.width.unwrap_or(0.0), height: layer.height, layer: RenderLayer::Background })
+        .with(sprite)
+        .with(Parallax { factor: layer.factor, repeat_x: layer.width.is_none() })
+        .build();
+}
+
+fn create_light(world: &mut World, light: &LightDef) {
+    world.create_entity()
+        .with(Position::new(Vector2::new(light.x, light.y)))
+        .with(Light::new(light.color, light.intensity, light.radius, light.shadows))
+        .build();
+}
+
+fn create_emitter(world: &mut World, emitter: &EmitterDef) {
+    world.create_entity()
+        .with(Position::new(Vector2::new(emitter.x, emitter.y)))
+        .with(ParticleEmitter::continuous(emitter.effect, emitter.rate))
+        .build();
 }
 
-fn create_player(world: &mut World, x: f32, y: f32) {
+fn create_level(world: &mut World, level: &LevelData) {
+    world.insert(Lighting { darkness: level.darkness });
+    for light in &level.lights {
+        create_light(world, light);
+    }
+    for layer in &level.parallax {
+        create_parallax_layer(world, layer);
+    }
+    for wall in &level.walls {
+        create_wall(world, wall);
+    }
+    for prop in &level.props {
+        create_prop(world, prop);
+    }
+    for decor in &level.decor {
+        create_decor(world, decor);
+    }
+    for emitter in &level.emitters {
+        create_emitter(world, emitter);
+    }
+}
+
+fn create_player(world: &mut World, x: f32, y: f32) -> Entity {
     let (rb_handle, col_handle) = {
         let mut pw = world.write_resource::<PhysicsWorld>();
         let pw = &mut *pw;
         let rigid_body_set = &mut pw.rigid_body_set;
         let collider_set = &mut pw.collider_set;
 
+        // Rotations are locked so the capsule stays upright while it swings as a dynamic body.
         let rigid_body = RigidBodyBuilder::kinematic_position_based()
             .translation(vector![x, y])
+            .lock_rotations()
             .build();
         
+        // Contact forces tell `PlayerControlSystem` when a prop hits the player.
         let collider = ColliderBuilder::capsule_y(10.0, 10.0)
+            .collision_groups(PLAYER_GROUPS)
+            .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
             .build();
         
         let rb_handle = rigid_body_set.insert(rigid_body);
@@ -316,12 +881,30 @@ fn create_player(world: &mut World, x: f32, y: f32) {
     };
 
     world.create_entity()
-        .with(Position(Vector2::new(x, y)))
-        .with(Renderable { color: [1.0, 0.5, 0.0, 1.0], width: 20.0, height: 40.0 })
+        .with(Position::new(Vector2::new(x, y)))
+        .with(Renderable { color: [1.0, 0.5, 0.0, 1.0], width: 20.0, height: 40.0, layer: RenderLayer::Player })
+        .with(Sprite::new("player/idle_0"))
+        // The player's lantern, so there is always something to see by.
+        .with(Light::new([1.0, 0.85, 0.6], 0.9, 240.0, ShadowMode::Soft))
+        .with(player_animation())
         .with(PhysicsBody { rigid_body_handle: rb_handle, collider_handle: col_handle })
         .with(Player)
         .with(character_controller)
-        .build();
+        .with(Hookshot::default())
+        .build()
+}
+
+/// The player's animation clips, all drawn from `assets/images/player`.
+fn player_animation() -> Animation {
+    Animation::new(vec![
+        ("idle", AnimationClip::new(&["player/idle_0", "player/idle_1"], 0.5, AnimationMode::Loop)),
+        ("run", AnimationClip::new(&["player/run_0", "player/run_1", "player/run_2", "player/run_3"], 0.1, AnimationMode::Loop)
+            .with_event(0, "footstep")
+            .with_event(2, "footstep")),
+        ("jump", AnimationClip::new(&["player/jump_0"], 0.1, AnimationMode::Once)),
+        ("fall", AnimationClip::new(&["player/fall_0"], 0.1, AnimationMode::Once)),
+        ("swing", AnimationClip::new(&["player/swing_0", "player/swing_1"], 0.25, AnimationMode::Loop)),
+    ], "idle")
 }
 
 
@@ -330,7 +913,14 @@ impl ApplicationHandler for App<'_> {
         let attributes = Window::default_attributes()
             .with_title("Shadow Chaser");
         let window = Box::leak(Box::new(event_loop.create_window(attributes).unwrap()));
-        self.state = Some(pollster::block_on(State::new(window)));
+        match pollster::block_on(State::new(window)) {
+            Ok(state) => self.state = Some(state),
+            Err(e) => {
+                log::error!("[Main] Failed to load the level: {}", e);
+                eprintln!("Failed to load the level: {}", e);
+                event_loop.exit();
+            }
+        }
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: winit::window::WindowId, event: WindowEvent) {
@@ -343,6 +933,10 @@ impl ApplicationHandler for App<'_> {
                     }
                     WindowEvent::RedrawRequested => {
                          state.update();
+                        if state.quit_requested() {
+                            event_loop.exit();
+                            return;
+                        }
                         match state.render() {
                             Ok(_) => {}
                             Err(wgpu::SurfaceError::Lost) => state.resize(state.size),
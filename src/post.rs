@@ -0,0 +1,392 @@
+// src/post.rs
+
+use crate::{assets, resources::GraphicsSettings};
+
+/// The scene is drawn into a float target, so bloom can pick out the highlights that
+/// lighting pushes past 1.0.
+pub const SCENE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+/// The color grading lookup table. See `fs_grade` in post.wgsl for its layout.
+const LUT_PATH: &str = "assets/luts/grade.png";
+/// How many levels of each channel the lookup table holds.
+const LUT_SIZE: u32 = 16;
+/// Bloom is blurred at a fraction of the screen's resolution, which is cheaper and
+/// spreads the glow further.
+const BLOOM_DOWNSCALE: u32 = 2;
+/// How bright a pixel must be to glow.
+const BLOOM_THRESHOLD: f32 = 0.8;
+/// How strongly the glow is added back.
+const BLOOM_INTENSITY: f32 = 0.6;
+/// How far the color channels split at the screen's edges at full hit strength, in
+/// fractions of the screen.
+const MAX_ABERRATION: f32 = 0.015;
+/// How dark the vignette gets in the corners.
+const VIGNETTE_STRENGTH: f32 = 0.45;
+
+/// The settings shared by every post-processing pass.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PostUniform {
+    bloom_threshold: f32,
+    bloom_intensity: f32,
+    aberration: f32,
+    vignette: f32,
+    /// The size of one texel of the bloom targets, in texture coordinates.
+    bloom_texel: [f32; 2],
+    /// 1.0 when the screen does not encode to sRGB by itself, so the final copy must.
+    encode_srgb: f32,
+    _padding: f32,
+}
+
+/// Runs the finished scene through a chain of fullscreen passes on its way to the
+/// screen: bloom, chromatic aberration, color grading and a vignette, each of which
+/// can be switched off in the `GraphicsSettings`.
+///
+/// The scene is drawn into one of two offscreen targets. Each enabled pass reads the
+/// latest image from one target and writes into the other. Once they are done,
+/// anything that should escape the effects can be drawn over `output_view`, and a
+/// final pass copies the result to the screen, encoding it to sRGB on the way.
+pub struct PostProcessor {
+    bright_pipeline: wgpu::RenderPipeline,
+    blur_horizontal_pipeline: wgpu::RenderPipeline,
+    blur_vertical_pipeline: wgpu::RenderPipeline,
+    bloom_pipeline: wgpu::RenderPipeline,
+    chromatic_pipeline: wgpu::RenderPipeline,
+    grade_pipeline: wgpu::RenderPipeline,
+    vignette_pipeline: wgpu::RenderPipeline,
+    blit_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    lut_view: wgpu::TextureView,
+    targets: Targets,
+    /// The scene target holding the latest image.
+    current: usize,
+    /// Whether the final copy must encode to sRGB itself.
+    encode_srgb: bool,
+    /// Which passes run this frame.
+    bloom: bool,
+    chromatic: bool,
+    grade: bool,
+    vignette: bool,
+}
+
+/// The offscreen targets, and the bind groups that read from them.
+struct Targets {
+    scene: [wgpu::TextureView; 2],
+    bloom: [wgpu::TextureView; 2],
+    /// Reads `scene[i]`, with the lookup table as the extra image.
+    scene_bind_groups: [wgpu::BindGroup; 2],
+    /// Reads `scene[i]`, with the blurred highlights as the extra image.
+    bloom_composite_bind_groups: [wgpu::BindGroup; 2],
+    /// Reads `bloom[i]`.
+    bloom_bind_groups: [wgpu::BindGroup; 2],
+    bloom_texel: [f32; 2],
+}
+
+impl PostProcessor {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, config: &wgpu::SurfaceConfiguration) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("post.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(concat!(include_str!("color.wgsl"), include_str!("post.wgsl")).into()),
+        });
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Post Bind Group Layout"),
+            entries: &[
+                texture_entry(0),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(3),
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label, entry_point, format| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_fullscreen",
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState { format, blend: None, write_mask: wgpu::ColorWrites::ALL })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Post Uniform Buffer"),
+            size: std::mem::size_of::<PostUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let lut_view = create_lut(device, queue);
+        let targets = Targets::new(device, &bind_group_layout, &sampler, &uniform_buffer, &lut_view, config.width, config.height);
+
+        Self {
+            bright_pipeline: pipeline("Bloom Bright Pipeline", "fs_bright", SCENE_FORMAT),
+            blur_horizontal_pipeline: pipeline("Bloom Horizontal Blur Pipeline", "fs_blur_horizontal", SCENE_FORMAT),
+            blur_vertical_pipeline: pipeline("Bloom Vertical Blur Pipeline", "fs_blur_vertical", SCENE_FORMAT),
+            bloom_pipeline: pipeline("Bloom Pipeline", "fs_bloom", SCENE_FORMAT),
+            chromatic_pipeline: pipeline("Chromatic Aberration Pipeline", "fs_chromatic", SCENE_FORMAT),
+            grade_pipeline: pipeline("Color Grade Pipeline", "fs_grade", SCENE_FORMAT),
+            vignette_pipeline: pipeline("Vignette Pipeline", "fs_vignette", SCENE_FORMAT),
+            blit_pipeline: pipeline("Blit Pipeline", "fs_blit", config.format),
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            lut_view,
+            targets,
+            current: 0,
+            encode_srgb: !config.format.is_srgb(),
+            bloom: false,
+            chromatic: false,
+            grade: false,
+            vignette: false,
+        }
+    }
+
+    /// Recreates the offscreen targets to match the new size of the screen.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.targets = Targets::new(device, &self.bind_group_layout, &self.sampler, &self.uniform_buffer, &self.lut_view, width, height);
+    }
+
+    /// The target the scene should be drawn into.
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        &self.targets.scene[0]
+    }
+
+    /// Picks this frame's passes and uploads their settings. `hit` is the strength of
+    /// the current hit effect, from 0.0 to 1.0.
+    pub fn prepare(&mut self, queue: &wgpu::Queue, settings: &GraphicsSettings, hit: f32) {
+        let aberration = hit * hit * MAX_ABERRATION;
+        self.bloom = settings.bloom;
+        // A split too small to see is not worth a pass.
+        self.chromatic = settings.chromatic_aberration && aberration > 1e-4;
+        self.grade = settings.color_grading;
+        self.vignette = settings.vignette;
+
+        let uniform = PostUniform {
+            bloom_threshold: BLOOM_THRESHOLD,
+            bloom_intensity: BLOOM_INTENSITY,
+            aberration,
+            vignette: VIGNETTE_STRENGTH,
+            bloom_texel: self.targets.bloom_texel,
+            encode_srgb: if self.encode_srgb { 1.0 } else { 0.0 },
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Runs the enabled passes over the scene.
+    pub fn apply(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let targets = &self.targets;
+        let mut current = 0;
+
+        if self.bloom {
+            run_pass(encoder, "Bloom Bright Pass", &self.bright_pipeline, &targets.scene_bind_groups[current], &targets.bloom[0]);
+            run_pass(encoder, "Bloom Horizontal Blur Pass", &self.blur_horizontal_pipeline, &targets.bloom_bind_groups[0], &targets.bloom[1]);
+            run_pass(encoder, "Bloom Vertical Blur Pass", &self.blur_vertical_pipeline, &targets.bloom_bind_groups[1], &targets.bloom[0]);
+            run_pass(encoder, "Bloom Pass", &self.bloom_pipeline, &targets.bloom_composite_bind_groups[current], &targets.scene[1 - current]);
+            current = 1 - current;
+        }
+
+        let passes = [
+            (self.chromatic, "Chromatic Aberration Pass", &self.chromatic_pipeline),
+            (self.grade, "Color Grade Pass", &self.grade_pipeline),
+            (self.vignette, "Vignette Pass", &self.vignette_pipeline),
+        ];
+        for (_, label, pipeline) in passes.into_iter().filter(|(enabled, _, _)| *enabled) {
+            run_pass(encoder, label, pipeline, &targets.scene_bind_groups[current], &targets.scene[1 - current]);
+            current = 1 - current;
+        }
+        self.current = current;
+    }
+
+    /// The target holding the processed image, once `apply` has run.
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.targets.scene[self.current]
+    }
+
+    /// Copies the processed image to `output`, the screen.
+    pub fn present(&self, encoder: &mut wgpu::CommandEncoder, output: &wgpu::TextureView) {
+        run_pass(encoder, "Blit Pass", &self.blit_pipeline, &self.targets.scene_bind_groups[self.current], output);
+    }
+}
+
+impl Targets {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        uniform_buffer: &wgpu::Buffer,
+        lut_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        let (bloom_width, bloom_height) = ((width / BLOOM_DOWNSCALE).max(1), (height / BLOOM_DOWNSCALE).max(1));
+        let scene = [
+            create_target(device, "Scene Target A", width, height),
+            create_target(device, "Scene Target B", width, height),
+        ];
+        let bloom = [
+            create_target(device, "Bloom Target A", bloom_width, bloom_height),
+            create_target(device, "Bloom Target B", bloom_width, bloom_height),
+        ];
+        let bind_group = |source: &wgpu::TextureView, extra: &wgpu::TextureView| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Post Bind Group"),
+                layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(sampler) },
+                    wgpu::BindGroupEntry { binding: 2, resource: uniform_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(extra) },
+                ],
+            })
+        };
+        Self {
+            scene_bind_groups: [bind_group(&scene[0], lut_view), bind_group(&scene[1], lut_view)],
+            bloom_composite_bind_groups: [bind_group(&scene[0], &bloom[0]), bind_group(&scene[1], &bloom[0])],
+            bloom_bind_groups: [bind_group(&bloom[0], lut_view), bind_group(&bloom[1], lut_view)],
+            bloom_texel: [1.0 / bloom_width as f32, 1.0 / bloom_height as f32],
+            scene,
+            bloom,
+        }
+    }
+}
+
+/// Draws a fullscreen pass with `pipeline`, reading through `bind_group` and writing to `target`.
+fn run_pass(encoder: &mut wgpu::CommandEncoder, label: &str, pipeline: &wgpu::RenderPipeline, bind_group: &wgpu::BindGroup, target: &wgpu::TextureView) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                // Every pass covers the whole target, so there is nothing worth loading.
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+    render_pass.set_pipeline(pipeline);
+    render_pass.set_bind_group(0, bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
+
+fn create_target(device: &wgpu::Device, label: &str, width: u32, height: u32) -> wgpu::TextureView {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: SCENE_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    }).create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Loads the color grading lookup table, falling back to one that leaves colors
+/// unchanged if it is missing or the wrong size.
+fn create_lut(device: &wgpu::Device, queue: &wgpu::Queue) -> wgpu::TextureView {
+    let (width, height) = (LUT_SIZE * LUT_SIZE, LUT_SIZE);
+    let path = assets::path(LUT_PATH);
+    let image = match image::open(&path) {
+        Ok(image) if image.width() == width && image.height() == height => image.to_rgba8(),
+        Ok(image) => {
+            log::warn!("[Post] {} is {}x{} rather than {}x{}; color grading is disabled", path.display(), image.width(), image.height(), width, height);
+            identity_lut()
+        }
+        Err(e) => {
+            log::warn!("[Post] Could not load {}: {}; color grading is disabled", path.display(), e);
+            identity_lut()
+        }
+    };
+
+    let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Color Grade LUT"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        // The table's colors are sRGB-encoded, but the shader works in that encoding
+        // directly, so the texture must not decode them.
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &image,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * width),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// A lookup table that maps every color to itself.
+fn identity_lut() -> image::RgbaImage {
+    let level = |i: u32| (i * 255 / (LUT_SIZE - 1)) as u8;
+    image::RgbaImage::from_fn(LUT_SIZE * LUT_SIZE, LUT_SIZE, |x, y| {
+        image::Rgba([level(x % LUT_SIZE), level(y), level(x / LUT_SIZE), 255])
+    })
+}
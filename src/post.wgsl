@@ -0,0 +1,143 @@
+// post.wgsl
+//
+// color.wgsl is prepended to this file.
+
+// The settings shared by every post-processing pass.
+// It must match the PostUniform struct in post.rs.
+struct PostUniform {
+    bloom_threshold: f32,
+    bloom_intensity: f32,
+    aberration: f32,
+    vignette: f32,
+    bloom_texel: vec2<f32>,
+    // 1.0 when the screen does not encode to sRGB by itself, so fs_blit must.
+    encode_srgb: f32,
+};
+
+// The image the pass works on.
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+@group(0) @binding(1)
+var s_source: sampler;
+@group(0) @binding(2)
+var<uniform> post: PostUniform;
+// A second image some passes need: the blurred highlights for fs_bloom, and the
+// color grading lookup table for fs_grade.
+@group(0) @binding(3)
+var t_extra: texture_2d<f32>;
+
+struct FullscreenOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// A single triangle large enough to cover the whole screen.
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> FullscreenOutput {
+    var out: FullscreenOutput;
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    return out;
+}
+
+// Copies the finished image to the screen.
+@fragment
+fn fs_blit(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(t_source, s_source, in.uv).rgb;
+    if post.encode_srgb > 0.5 {
+        color = to_srgb(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)));
+    }
+    return vec4<f32>(color, 1.0);
+}
+
+// --- Bloom ---
+
+// Keeps only the part of each pixel brighter than the threshold.
+@fragment
+fn fs_bright(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_source, s_source, in.uv).rgb;
+    let brightness = max(color.r, max(color.g, color.b));
+    let contribution = max(brightness - post.bloom_threshold, 0.0) / max(brightness, 0.0001);
+    return vec4<f32>(color * contribution, 1.0);
+}
+
+// One half of a separable Gaussian blur, stepping `direction` per tap.
+fn blur(uv: vec2<f32>, direction: vec2<f32>) -> vec4<f32> {
+    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+    var sum = textureSample(t_source, s_source, uv).rgb * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = direction * f32(i);
+        sum += textureSample(t_source, s_source, uv + offset).rgb * weights[i];
+        sum += textureSample(t_source, s_source, uv - offset).rgb * weights[i];
+    }
+    return vec4<f32>(sum, 1.0);
+}
+
+@fragment
+fn fs_blur_horizontal(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    return blur(in.uv, vec2<f32>(post.bloom_texel.x, 0.0));
+}
+
+@fragment
+fn fs_blur_vertical(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    return blur(in.uv, vec2<f32>(0.0, post.bloom_texel.y));
+}
+
+// Adds the blurred highlights back over the image.
+@fragment
+fn fs_bloom(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_source, s_source, in.uv).rgb;
+    let glow = textureSample(t_extra, s_source, in.uv).rgb;
+    return vec4<f32>(color + glow * post.bloom_intensity, 1.0);
+}
+
+// --- Chromatic aberration ---
+
+// Pulls the red and blue channels apart, more so towards the edges of the screen.
+@fragment
+fn fs_chromatic(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let offset = (in.uv - vec2<f32>(0.5)) * post.aberration;
+    let r = textureSample(t_source, s_source, in.uv + offset).r;
+    let g = textureSample(t_source, s_source, in.uv).g;
+    let b = textureSample(t_source, s_source, in.uv - offset).b;
+    return vec4<f32>(r, g, b, 1.0);
+}
+
+// --- Color grade ---
+
+// The lookup table is a 16x16x16 cube laid out as a strip of 16 slices, one per
+// blue level, each with red running across and green running down. Its colors are
+// sRGB-encoded, the way image editors show them.
+const LUT_SIZE: f32 = 16.0;
+
+@fragment
+fn fs_grade(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = clamp(to_srgb(max(textureSample(t_source, s_source, in.uv).rgb, vec3<f32>(0.0))), vec3<f32>(0.0), vec3<f32>(1.0));
+    // Blend between the two slices either side of the blue level; the sampler
+    // blends red and green within each slice.
+    let blue = color.b * (LUT_SIZE - 1.0);
+    let slice = floor(blue);
+    let next_slice = min(slice + 1.0, LUT_SIZE - 1.0);
+    let texel = color.rg * (LUT_SIZE - 1.0) + vec2<f32>(0.5);
+    let uv = vec2<f32>(texel.x / (LUT_SIZE * LUT_SIZE), texel.y / LUT_SIZE);
+    let step = 1.0 / LUT_SIZE;
+    let graded = mix(
+        textureSample(t_extra, s_source, uv + vec2<f32>(slice * step, 0.0)).rgb,
+        textureSample(t_extra, s_source, uv + vec2<f32>(next_slice * step, 0.0)).rgb,
+        blue - slice,
+    );
+    return vec4<f32>(to_linear(graded), 1.0);
+}
+
+// --- Vignette ---
+
+// Darkens the corners of the screen.
+@fragment
+fn fs_vignette(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_source, s_source, in.uv).rgb;
+    let distance = length(in.uv - vec2<f32>(0.5)) * 1.4142;
+    let shade = 1.0 - post.vignette * smoothstep(0.4, 1.0, distance);
+    return vec4<f32>(color * shade, 1.0);
+}
@@ -2,8 +2,11 @@
 
 use rapier2d::prelude::*;
 use std::collections::HashSet;
+use winit::event::MouseButton;
 use winit::keyboard::KeyCode;
 use crossbeam::channel::{unbounded, Receiver};
+use specs::Entity;
+use crate::components::{ParticleEffect, ParticleSettings};
 
 /// A resource that holds the entire rapier2d physics simulation state.
 pub struct PhysicsWorld {
@@ -24,7 +27,7 @@ pub struct PhysicsWorld {
     pub physics_hooks: (),
     pub event_handler: ChannelEventCollector,
     pub _collision_event_receiver: Receiver<CollisionEvent>,
-    pub _contact_force_event_receiver: Receiver<ContactForceEvent>,
+    pub contact_force_event_receiver: Receiver<ContactForceEvent>,
 }
 
 impl Default for PhysicsWorld {
@@ -49,29 +52,265 @@ impl Default for PhysicsWorld {
             physics_hooks: (),
             event_handler,
             _collision_event_receiver: collision_receiver,
-            _contact_force_event_receiver: contact_force_receiver,
+            contact_force_event_receiver: contact_force_receiver,
         }
     }
 }
 
-/// A resource to hold the vertex data that needs to be rendered each frame.
+/// A resource tracking the passage of game time.
+///
+/// The simulation always ticks at `fixed_dt` of real time, but each tick advances the
+/// game by `dt = fixed_dt * time_scale`. Gameplay systems should read `dt` rather than
+/// `PhysicsWorld::integration_parameters`.
+pub struct GameTime {
+    /// The number of simulation ticks run so far.
+    pub tick: u64,
+    /// The real time covered by one simulation tick, in seconds.
+    pub fixed_dt: f32,
+    /// The game time covered by one simulation tick, in seconds.
+    pub dt: f32,
+    /// The real time that passed since the previous frame, in seconds.
+    pub real_dt: f32,
+    /// The total game time simulated so far, in seconds.
+    pub elapsed: f32,
+    /// How fast game time runs relative to real time. 1.0 is normal speed.
+    pub time_scale: f32,
+    /// While paused, no simulation ticks run at all.
+    pub paused: bool,
+}
+
+impl Default for GameTime {
+    fn default() -> Self {
+        let fixed_dt = 1.0 / 60.0;
+        Self {
+            tick: 0,
+            fixed_dt,
+            dt: fixed_dt,
+            real_dt: 0.0,
+            elapsed: 0.0,
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl GameTime {
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+        self.dt = self.fixed_dt * self.time_scale;
+    }
+
+    /// Records that one simulation tick has run.
+    pub fn advance(&mut self) {
+        self.tick += 1;
+        self.elapsed += self.dt;
+    }
+}
+
+/// How far the current frame lies between the last two physics ticks, from 0.0 to 1.0.
+/// Rendering blends each entity's previous and current positions by this amount.
 #[derive(Default)]
-pub struct RenderData(pub Vec<Vertex>);
+pub struct RenderAlpha(pub f32);
 
-/// The vertex structure that is sent to the GPU.
+/// A resource to hold the quad instances that need to be rendered each frame.
+#[derive(Default)]
+pub struct RenderData {
+    /// The world's quads, drawn back to front and then lit.
+    pub instances: Vec<Instance>,
+    /// Quads drawn in world space over the lit scene, untouched by lighting.
+    pub overlay: Vec<Instance>,
+    /// Quads drawn in screen pixels over everything else, ignoring the camera.
+    pub ui: Vec<Instance>,
+}
+
+/// A resource holding the debug overlay's settings.
+#[derive(Default)]
+pub struct DebugDraw {
+    /// Draws collider outlines, velocities, contacts, joints and hookshot rays over the scene.
+    pub enabled: bool,
+}
+
+/// A corner of the unit quad every instance is drawn from. The quad is centered on
+/// the origin and one unit across.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
-    pub position: [f32; 2],
-    pub color: [f32; 4],
+    pub corner: [f32; 2],
 }
 
+/// The unit quad as two triangles.
+pub const QUAD_VERTICES: [Vertex; 6] = [
+    Vertex { corner: [-0.5, -0.5] },
+    Vertex { corner: [0.5, -0.5] },
+    Vertex { corner: [0.5, 0.5] },
+    Vertex { corner: [-0.5, -0.5] },
+    Vertex { corner: [0.5, 0.5] },
+    Vertex { corner: [-0.5, 0.5] },
+];
+
 impl Vertex {
-    /// Describes the memory layout of the vertex buffer to wgpu.
+    /// Describes the memory layout of the quad's vertex buffer to wgpu.
     pub fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+}
+
+/// One quad to draw, sent to the GPU once per instance of the unit quad.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Instance {
+    /// The quad's center, in world space.
+    pub position: [f32; 2],
+    /// The quad's width and height, in world units.
+    pub size: [f32; 2],
+    /// The quad's rotation about its center, in radians counter-clockwise.
+    pub rotation: f32,
+    pub color: [f32; 4],
+    /// The sprite's region of the atlas as `[u, v, width, height]`.
+    pub uv_rect: [f32; 4],
+    /// How many times the sprite repeats across the quad on each axis. 1.0 stretches
+    /// the image over the whole quad.
+    pub uv_scale: [f32; 2],
+    /// Shifts the sprite across the quad, in repeats of the image. Scrolling strips use
+    /// this to keep the texture fixed in place while the quad follows the camera.
+    pub uv_offset: [f32; 2],
+}
+
+impl Instance {
+    /// Describes the memory layout of the instance buffer to wgpu.
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 13]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 15]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+
+    /// A flat-colored line from `a` to `b`, `width` units thick. `uv_rect` should be
+    /// the atlas's white region.
+    pub fn line(a: nalgebra::Vector2<f32>, b: nalgebra::Vector2<f32>, width: f32, color: [f32; 4], uv_rect: [f32; 4]) -> Self {
+        let delta = b - a;
+        Self {
+            position: ((a + b) / 2.0).into(),
+            size: [delta.norm(), width],
+            rotation: delta.y.atan2(delta.x),
+            color,
+            uv_rect,
+            uv_scale: [1.0, 1.0],
+            uv_offset: [0.0, 0.0],
+        }
+    }
+}
+
+/// A resource holding the level's lighting settings.
+#[derive(Default)]
+pub struct Lighting {
+    /// How dark the level is where no light reaches: 0.0 is fully lit and 1.0 is black.
+    pub darkness: f32,
+}
+
+/// Something to draw on the UI layer. Positions and sizes are in window pixels,
+/// measured from the top-left corner.
+#[derive(Debug, Clone)]
+pub enum UiCommand {
+    /// A flat-colored rectangle with its top-left corner at `pos`.
+    Rect {
+        pos: nalgebra::Vector2<f32>,
+        size: nalgebra::Vector2<f32>,
+        color: [f32; 4],
+    },
+    /// A string of text with its top-left corner at `pos`, `size` pixels tall per line.
+    Text {
+        pos: nalgebra::Vector2<f32>,
+        size: f32,
+        color: [f32; 4],
+        text: String,
+    },
+}
+
+/// A resource queueing what to draw on the UI layer this frame, in the order it is
+/// drawn. The UI layer sits over everything else and ignores the camera.
+///
+/// Systems in the render dispatcher queue into it every frame; the `UiSystem` drains
+/// it once they are done.
+#[derive(Default)]
+pub struct UiDrawList(pub Vec<UiCommand>);
+
+impl UiDrawList {
+    pub fn draw_text(&mut self, pos: nalgebra::Vector2<f32>, size: f32, color: [f32; 4], text: &str) {
+        self.0.push(UiCommand::Text { pos, size, color, text: text.to_string() });
+    }
+
+    pub fn draw_rect(&mut self, pos: nalgebra::Vector2<f32>, size: nalgebra::Vector2<f32>, color: [f32; 4]) {
+        self.0.push(UiCommand::Rect { pos, size, color });
+    }
+}
+
+/// A vertex of the lighting pass: either a corner of a shadow volume or of a light's quad.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightVertex {
+    /// The vertex position, in world space.
+    pub position: [f32; 2],
+    /// The vertex position relative to the light, where 1.0 is the light's radius.
+    /// Unused by shadow volumes.
+    pub local: [f32; 2],
+    /// The light's color scaled by its intensity. Unused by shadow volumes.
+    pub color: [f32; 4],
+}
+
+impl LightVertex {
+    /// Describes the memory layout of the lighting vertex buffer to wgpu.
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<LightVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
             attributes: &[
                 wgpu::VertexAttribute {
                     offset: 0,
@@ -81,6 +320,11 @@ impl Vertex {
                 wgpu::VertexAttribute {
                     offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
                     shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
                     format: wgpu::VertexFormat::Float32x4,
                 },
             ],
@@ -88,12 +332,149 @@ impl Vertex {
     }
 }
 
+/// One sample of one light: the shadow volumes to stencil out, then the light's quad.
+#[derive(Debug, Clone)]
+pub struct LightDraw {
+    pub shadows: std::ops::Range<u32>,
+    pub light: std::ops::Range<u32>,
+}
+
+/// A resource holding the lighting pass's geometry for the current frame, in the
+/// order it must be drawn.
+#[derive(Default)]
+pub struct LightData {
+    pub vertices: Vec<LightVertex>,
+    pub draws: Vec<LightDraw>,
+}
+
+/// A single live particle.
+#[derive(Debug, Clone, Copy)]
+pub struct Particle {
+    pub position: nalgebra::Vector2<f32>,
+    /// Where the particle was on the previous tick, for interpolation.
+    pub previous: nalgebra::Vector2<f32>,
+    pub velocity: nalgebra::Vector2<f32>,
+    /// How long the particle has been alive, in seconds.
+    pub age: f32,
+    pub lifetime: f32,
+    pub settings: ParticleSettings,
+}
+
+impl Particle {
+    /// How far through its life the particle is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+/// A resource holding every live particle. Particles are plain data rather than
+/// entities, since there are many of them and they never interact with anything.
+#[derive(Default)]
+pub struct Particles(pub Vec<Particle>);
+
+/// A one-off burst of particles requested by gameplay.
+#[derive(Debug, Clone, Copy)]
+pub struct ParticleBurst {
+    pub effect: ParticleEffect,
+    pub position: nalgebra::Vector2<f32>,
+    /// The direction to throw the particles in, in radians, for effects that aim.
+    pub direction: f32,
+}
+
+/// A resource queueing the particle bursts requested during the current tick.
+/// It is drained by the `ParticleSystem` at the end of every tick.
+#[derive(Default)]
+pub struct ParticleBursts(pub Vec<ParticleBurst>);
+
+impl ParticleBursts {
+    pub fn spawn(&mut self, effect: ParticleEffect, position: nalgebra::Vector2<f32>, direction: f32) {
+        self.0.push(ParticleBurst { effect, position, direction });
+    }
+}
+
 /// A resource to hold the current state of user input.
 #[derive(Default)]
 pub struct InputState {
     pub pressed_keys: HashSet<KeyCode>,
     /// A flag that is true only for the single frame a jump is initiated.
     pub jump_pressed: bool,
+    /// The mouse buttons currently held down.
+    pub pressed_mouse_buttons: HashSet<MouseButton>,
+    /// The cursor position in window pixels, measured from the top-left corner.
+    pub cursor_position: nalgebra::Vector2<f32>,
+    /// A flag that is true only for the single frame the hookshot is fired.
+    pub fire_pressed: bool,
+    /// A flag that is true only for the single frame the hookshot is let go.
+    pub release_pressed: bool,
+    /// Menu actions pressed since the last frame was drawn.
+    pub menu: MenuInput,
+}
+
+/// The actions menus respond to. Unlike the gameplay flags in `InputState`, which
+/// last until the next simulation tick, these last until the next frame is drawn,
+/// since menus keep working while the game is paused.
+///
+/// The keyboard, the mouse and any connected gamepad all set them.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct MenuInput {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    pub confirm: bool,
+    pub back: bool,
+    /// The left mouse button was pressed.
+    pub click: bool,
+}
+
+impl MenuInput {
+    /// Records the menu action, if any, that `key` is bound to.
+    pub fn press_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::ArrowUp | KeyCode::KeyW => self.up = true,
+            KeyCode::ArrowDown | KeyCode::KeyS => self.down = true,
+            KeyCode::ArrowLeft | KeyCode::KeyA => self.left = true,
+            KeyCode::ArrowRight | KeyCode::KeyD => self.right = true,
+            KeyCode::Enter | KeyCode::Space => self.confirm = true,
+            KeyCode::Escape => self.back = true,
+            _ => {}
+        }
+    }
+
+    /// Records the menu action, if any, that the gamepad `button` is bound to. The
+    /// d-pad navigates, the bottom face button confirms, and the right face button or
+    /// Start goes back.
+    pub fn press_button(&mut self, button: gilrs::Button) {
+        match button {
+            gilrs::Button::DPadUp => self.up = true,
+            gilrs::Button::DPadDown => self.down = true,
+            gilrs::Button::DPadLeft => self.left = true,
+            gilrs::Button::DPadRight => self.right = true,
+            gilrs::Button::South => self.confirm = true,
+            gilrs::Button::East | gilrs::Button::Start => self.back = true,
+            _ => {}
+        }
+    }
+}
+
+/// The screens of the in-game menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MenuScreen {
+    /// No menu is showing and the game is being played.
+    #[default]
+    Closed,
+    Pause,
+    Options,
+    /// Reached from Options.
+    Graphics,
+}
+
+/// A resource holding the state of the in-game menu.
+#[derive(Default)]
+pub struct Menu {
+    pub screen: MenuScreen,
+    /// Set when the player picks Quit; the window closes after the frame.
+    pub quit_requested: bool,
 }
 
 /// A resource to hold the current dimensions of the window.
@@ -102,3 +483,290 @@ pub struct ScreenDimensions {
     pub width: f32,
     pub height: f32,
 }
+
+/// A resource describing the 2D camera the world is viewed through.
+pub struct Camera {
+    /// The world position at the center of the screen.
+    pub position: nalgebra::Vector2<f32>,
+    /// Screen pixels per world unit.
+    pub zoom: f32,
+    /// The entity the camera follows, if any.
+    pub target: Option<Entity>,
+    /// Half the size of the box around the camera's focus that the target can move
+    /// around in without the camera following.
+    pub dead_zone: nalgebra::Vector2<f32>,
+    /// Roughly how long, in seconds, the camera takes to catch up with its target.
+    pub smooth_time: f32,
+    /// How many seconds of the target's velocity the camera looks ahead by.
+    pub look_ahead_time: f32,
+    /// The furthest the camera looks ahead of its target, in world units.
+    pub max_look_ahead: f32,
+    /// The level's bounds as `(min, max)` corners. The camera never shows anything outside them.
+    pub bounds: Option<(nalgebra::Vector2<f32>, nalgebra::Vector2<f32>)>,
+    /// Where the camera is heading, before smoothing.
+    pub focus: nalgebra::Vector2<f32>,
+    /// The camera's current velocity, carried between frames by the smoothing.
+    pub velocity: nalgebra::Vector2<f32>,
+    /// The positional part of the current screen shake, in world units.
+    pub shake_offset: nalgebra::Vector2<f32>,
+    /// The rotational part of the current screen shake, in radians.
+    pub shake_angle: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            position: nalgebra::Vector2::zeros(),
+            zoom: 1.0,
+            target: None,
+            dead_zone: nalgebra::vector![40.0, 30.0],
+            smooth_time: 0.2,
+            look_ahead_time: 0.25,
+            max_look_ahead: 120.0,
+            bounds: None,
+            focus: nalgebra::Vector2::zeros(),
+            velocity: nalgebra::Vector2::zeros(),
+            shake_offset: nalgebra::Vector2::zeros(),
+            shake_angle: 0.0,
+        }
+    }
+}
+
+impl Camera {
+    /// Half the size of the visible area, in world units.
+    pub fn half_extents(&self, screen: &ScreenDimensions) -> nalgebra::Vector2<f32> {
+        nalgebra::vector![screen.width, screen.height] / (2.0 * self.zoom)
+    }
+
+    /// The matrix taking world coordinates to clip space, including any screen shake.
+    pub fn view_proj(&self, screen: &ScreenDimensions) -> nalgebra::Matrix4<f32> {
+        let half = self.half_extents(screen);
+        let center = self.position + self.shake_offset;
+        let scale = nalgebra::Matrix4::new_nonuniform_scaling(&nalgebra::vector![1.0 / half.x, 1.0 / half.y, 1.0]);
+        let rotation = nalgebra::Matrix4::new_rotation(nalgebra::vector![0.0, 0.0, -self.shake_angle]);
+        let translation = nalgebra::Matrix4::new_translation(&nalgebra::vector![-center.x, -center.y, 0.0]);
+        scale * rotation * translation
+    }
+
+    /// Converts a window pixel position (origin top-left, y down) into world
+    /// coordinates (y up). It inverts `view_proj`, so the point under the cursor stays
+    /// under it while the camera shakes.
+    pub fn screen_to_world(&self, screen: &ScreenDimensions, pixel: nalgebra::Vector2<f32>) -> nalgebra::Vector2<f32> {
+        let clip = nalgebra::point![pixel.x / screen.width * 2.0 - 1.0, 1.0 - pixel.y / screen.height * 2.0, 0.0];
+        let world = self.view_proj(screen).try_inverse()
+            .map_or(nalgebra::Point3::from(self.position.push(0.0)), |inverse| inverse.transform_point(&clip));
+        nalgebra::vector![world.x, world.y]
+    }
+}
+
+/// A resource that collects camera shake from around the game.
+///
+/// Systems call `add_trauma` when something hits hard; the `CameraSystem` turns the
+/// accumulated trauma into a short, decaying shake. Shake strength grows with the
+/// square of trauma, so small bumps stay subtle while big hits stack up quickly.
+pub struct CameraShake {
+    /// The current trauma, from 0.0 (still) to 1.0 (maximum shake).
+    pub trauma: f32,
+    /// How much trauma wears off per second.
+    pub decay: f32,
+    /// How far the camera is offset at maximum shake, in world units.
+    pub max_offset: f32,
+    /// How far the camera is rotated at maximum shake, in radians.
+    pub max_angle: f32,
+    /// How quickly the shake wobbles.
+    pub frequency: f32,
+    /// The running clock the shake's noise is sampled at.
+    pub time: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: 14.0,
+            max_angle: 0.06,
+            frequency: 25.0,
+            time: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    /// Requests a shake. `amount` is added to the current trauma, which is capped at 1.0.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+/// A resource that collects hits the player takes, for the screen's hit effect.
+///
+/// Systems call `add_hit` when the player is struck; the post-processing chain splits
+/// the screen's color channels apart by the current strength, which the
+/// `CameraSystem` lets fade away.
+pub struct HitEffect {
+    /// The current strength, from 0.0 (none) to 1.0 (strongest).
+    pub strength: f32,
+    /// How much strength wears off per second.
+    pub decay: f32,
+}
+
+impl Default for HitEffect {
+    fn default() -> Self {
+        Self { strength: 0.0, decay: 2.5 }
+    }
+}
+
+impl HitEffect {
+    /// Registers a hit. `amount` is added to the current strength, which is capped at 1.0.
+    pub fn add_hit(&mut self, amount: f32) {
+        self.strength = (self.strength + amount).clamp(0.0, 1.0);
+    }
+}
+
+/// A resource holding the player's graphics options. They are loaded from and saved
+/// to the settings file by `settings.rs`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphicsSettings {
+    /// Makes the brightest parts of the screen glow.
+    pub bloom: bool,
+    /// Darkens the corners of the screen.
+    pub vignette: bool,
+    /// Splits the screen's colors apart when the player is hit.
+    pub chromatic_aberration: bool,
+    /// Grades the screen's colors through `assets/luts/grade.png`.
+    pub color_grading: bool,
+    /// How finished frames are handed to the screen.
+    pub present_mode: PresentMode,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            bloom: true,
+            vignette: true,
+            chromatic_aberration: true,
+            color_grading: true,
+            present_mode: PresentMode::default(),
+        }
+    }
+}
+
+/// How finished frames are handed to the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PresentMode {
+    /// Waits for the display's refresh, so frames never tear. Supported everywhere.
+    #[default]
+    Vsync,
+    /// Shows the newest finished frame at each refresh, without tearing or waiting
+    /// on the display, at the cost of drawing frames that are never shown.
+    Mailbox,
+    /// Shows each frame as soon as it is finished, tearing and all.
+    Immediate,
+}
+
+impl PresentMode {
+    /// Every mode, in the order the options menu lists them.
+    pub const ALL: [Self; 3] = [Self::Vsync, Self::Mailbox, Self::Immediate];
+
+    /// The mode's name as the options menu shows it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Vsync => "Vsync",
+            Self::Mailbox => "Mailbox",
+            Self::Immediate => "Immediate",
+        }
+    }
+
+    pub fn to_wgpu(self) -> wgpu::PresentMode {
+        match self {
+            Self::Vsync => wgpu::PresentMode::Fifo,
+            Self::Mailbox => wgpu::PresentMode::Mailbox,
+            Self::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
+
+/// The camera data that is sent to the GPU as a uniform.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CameraUniform {
+    pub view_proj: [[f32; 4]; 4],
+}
+
+impl CameraUniform {
+    pub fn new(camera: &Camera, screen: &ScreenDimensions) -> Self {
+        Self { view_proj: camera.view_proj(screen).into() }
+    }
+
+    /// A projection taking window pixels (origin top-left, y down) to clip space, for
+    /// drawing the UI layer.
+    pub fn screen(screen: &ScreenDimensions) -> Self {
+        let scale = nalgebra::Matrix4::new_nonuniform_scaling(&nalgebra::vector![2.0 / screen.width, -2.0 / screen.height, 1.0]);
+        let translation = nalgebra::Matrix4::new_translation(&nalgebra::vector![-screen.width / 2.0, -screen.height / 2.0, 0.0]);
+        Self { view_proj: (scale * translation).into() }
+    }
+}
+
+/// The outcome of firing the hookshot.
+#[derive(Debug, Clone, Copy)]
+pub enum HookshotEvent {
+    /// The hook struck a collider within range.
+    Hit {
+        origin: nalgebra::Vector2<f32>,
+        point: nalgebra::Vector2<f32>,
+        normal: nalgebra::Vector2<f32>,
+        collider: ColliderHandle,
+        distance: f32,
+    },
+    /// Nothing lies along the aim direction at all.
+    Miss {
+        origin: nalgebra::Vector2<f32>,
+        direction: nalgebra::Vector2<f32>,
+    },
+    /// There is something to hook onto, but it is beyond the hookshot's range.
+    OutOfRange {
+        origin: nalgebra::Vector2<f32>,
+        point: nalgebra::Vector2<f32>,
+        distance: f32,
+    },
+}
+
+/// A resource holding the hookshot events produced during the current tick.
+/// It is cleared by the `HookshotSystem` at the start of every tick.
+#[derive(Default)]
+pub struct HookshotEvents(pub Vec<HookshotEvent>);
+
+/// A frame event raised by an entity's `Animation`, such as a footstep.
+#[derive(Debug, Clone)]
+pub struct AnimationEvent {
+    pub entity: Entity,
+    /// The event attached to the frame that was reached.
+    pub name: String,
+}
+
+/// A resource holding the animation events raised during the current tick.
+/// It is cleared by the `AnimationSystem` at the start of every tick.
+#[derive(Default)]
+pub struct AnimationEvents(pub Vec<AnimationEvent>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_to_world_inverts_the_shaken_view() {
+        let screen = ScreenDimensions { width: 800.0, height: 600.0 };
+        let camera = Camera {
+            position: nalgebra::vector![120.0, -40.0],
+            zoom: 2.0,
+            shake_offset: nalgebra::vector![6.0, -3.0],
+            shake_angle: 0.05,
+            ..Default::default()
+        };
+        let world = nalgebra::vector![150.0, -10.0];
+        let clip = camera.view_proj(&screen).transform_point(&nalgebra::point![world.x, world.y, 0.0]);
+        let pixel = nalgebra::vector![(clip.x + 1.0) / 2.0 * screen.width, (1.0 - clip.y) / 2.0 * screen.height];
+        assert!((camera.screen_to_world(&screen, pixel) - world).norm() < 1e-3);
+    }
+}
@@ -0,0 +1,131 @@
+// src/settings.rs
+
+use std::path::PathBuf;
+use crate::resources::{GraphicsSettings, PresentMode};
+
+/// The settings file, kept next to the executable.
+const FILE_NAME: &str = "settings.txt";
+
+/// Where the settings file lives. Each build keeps its own, beside its executable.
+fn path() -> PathBuf {
+    std::env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(FILE_NAME)))
+        .unwrap_or_else(|| PathBuf::from(FILE_NAME))
+}
+
+/// Reads the player's graphics settings. A missing file gives the defaults, and so
+/// does a broken one, after a warning: bad settings should never stop the game.
+pub fn load() -> GraphicsSettings {
+    let path = path();
+    let source = match std::fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            log::info!("[Settings] No settings at {}; using the defaults", path.display());
+            return GraphicsSettings::default();
+        }
+        Err(e) => {
+            log::warn!("[Settings] Could not read {}: {}; using the defaults", path.display(), e);
+            return GraphicsSettings::default();
+        }
+    };
+    parse(&source).unwrap_or_else(|e| {
+        log::warn!("[Settings] {}: {}; using the defaults", path.display(), e);
+        GraphicsSettings::default()
+    })
+}
+
+/// Writes the player's graphics settings, logging rather than failing if it can't.
+pub fn save(settings: &GraphicsSettings) {
+    let path = path();
+    match std::fs::write(&path, format(settings)) {
+        Ok(()) => log::info!("[Settings] Saved to {}", path.display()),
+        Err(e) => log::warn!("[Settings] Could not write {}: {}", path.display(), e),
+    }
+}
+
+/// Parses settings from the `key value` lines `format` writes. Keys that are left out
+/// keep their defaults.
+fn parse(source: &str) -> Result<GraphicsSettings, String> {
+    let mut settings = GraphicsSettings::default();
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line.split_once(char::is_whitespace)
+            .ok_or_else(|| format!("line {}: `{}` is missing its value", line_number, line))?;
+        let value = value.trim();
+        let switch = || match value {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(format!("line {}: `{}` should be `on` or `off`", line_number, value)),
+        };
+
+        match key {
+            "bloom" => settings.bloom = switch()?,
+            "vignette" => settings.vignette = switch()?,
+            "chromatic_aberration" => settings.chromatic_aberration = switch()?,
+            "color_grading" => settings.color_grading = switch()?,
+            "present_mode" => {
+                settings.present_mode = PresentMode::ALL.into_iter()
+                    .find(|mode| mode.name().eq_ignore_ascii_case(value))
+                    .ok_or_else(|| format!("line {}: unknown present mode `{}`", line_number, value))?;
+            }
+            _ => return Err(format!("line {}: unknown setting `{}`", line_number, key)),
+        }
+    }
+    Ok(settings)
+}
+
+/// Writes settings out in the format `parse` reads.
+fn format(settings: &GraphicsSettings) -> String {
+    let switch = |on: bool| if on { "on" } else { "off" };
+    format!(
+        "# Shadow Chaser graphics settings. The game rewrites this file whenever they change.\n\
+         # Turn the post-processing passes off for a faster game on low-end machines.\n\
+         bloom {}\n\
+         vignette {}\n\
+         chromatic_aberration {}\n\
+         color_grading {}\n\
+         # vsync, mailbox or immediate\n\
+         present_mode {}\n",
+        switch(settings.bloom),
+        switch(settings.vignette),
+        switch(settings.chromatic_aberration),
+        switch(settings.color_grading),
+        settings.present_mode.name().to_lowercase(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_file_format() {
+        let settings = GraphicsSettings {
+            bloom: false,
+            vignette: true,
+            chromatic_aberration: false,
+            color_grading: true,
+            present_mode: PresentMode::Mailbox,
+        };
+        assert_eq!(parse(&format(&settings)).unwrap(), settings);
+    }
+
+    #[test]
+    fn missing_keys_keep_their_defaults() {
+        let settings = parse("# Only bloom is set.\nbloom off\n").unwrap();
+        assert_eq!(settings, GraphicsSettings { bloom: false, ..GraphicsSettings::default() });
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        assert_eq!(parse("bloom").unwrap_err(), "line 1: `bloom` is missing its value");
+        assert_eq!(parse("\nvignette maybe").unwrap_err(), "line 2: `maybe` should be `on` or `off`");
+        assert_eq!(parse("present_mode adaptive").unwrap_err(), "line 1: unknown present mode `adaptive`");
+        assert_eq!(parse("shadows on").unwrap_err(), "line 1: unknown setting `shadows`");
+    }
+}
@@ -1,10 +1,35 @@
 // shader.wgsl
+//
+// color.wgsl is prepended to this file.
 
-// The input structure for the vertex shader.
-// It must match the Vertex struct in resources.rs.
+// The camera transform, uploaded once per frame.
+// It must match the CameraUniform struct in resources.rs.
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+// The sprite atlas every quad samples from. Untextured quads sample a white texel.
+@group(1) @binding(0)
+var t_atlas: texture_2d<f32>;
+@group(1) @binding(1)
+var s_atlas: sampler;
+
+// A corner of the unit quad. It must match the Vertex struct in resources.rs.
 struct VertexInput {
-    @location(0) position: vec2<f32>,
-    @location(1) color: vec4<f32>,
+    @location(0) corner: vec2<f32>,
+};
+
+// One quad to draw. It must match the Instance struct in resources.rs.
+struct InstanceInput {
+    @location(1) position: vec2<f32>,
+    @location(2) size: vec2<f32>,
+    @location(3) rotation: f32,
+    @location(4) color: vec4<f32>,
+    @location(5) uv_rect: vec4<f32>,
+    @location(6) uv_scale: vec2<f32>,
+    @location(7) uv_offset: vec2<f32>,
 };
 
 // The output structure for the vertex shader, which becomes the
@@ -12,20 +37,32 @@ struct VertexInput {
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) uv_rect: vec4<f32>,
 };
 
 @vertex
-fn vs_main(model: VertexInput) -> VertexOutput {
+fn vs_main(model: VertexInput, instance: InstanceInput) -> VertexOutput {
     var out: VertexOutput;
-    // The position is already in clip-space, so we just need to convert
-    // it to a vec4 for the @builtin(position).
-    out.clip_position = vec4<f32>(model.position, 0.0, 1.0);
-    out.color = model.color;
+    // Scale the unit quad to the instance's size, spin it about its center and
+    // move it into place in world space; the camera then takes it to clip space.
+    let local = model.corner * instance.size;
+    let c = cos(instance.rotation);
+    let s = sin(instance.rotation);
+    let world = instance.position + vec2<f32>(local.x * c - local.y * s, local.x * s + local.y * c);
+    out.clip_position = camera.view_proj * vec4<f32>(world, 0.0, 1.0);
+    // Colors are authored in sRGB but blended in linear light.
+    out.color = vec4<f32>(to_linear(instance.color.rgb), instance.color.a);
+    // Texture coordinates run top to bottom, while world coordinates run bottom to top.
+    out.uv = vec2<f32>(model.corner.x + 0.5, 0.5 - model.corner.y) * instance.uv_scale + instance.uv_offset;
+    out.uv_rect = instance.uv_rect;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // The fragment shader simply returns the color passed from the vertex shader.
-    return in.color;
+    // Wrap the sprite's coordinates so tiled sprites repeat, then map them into
+    // the sprite's region of the atlas. The vertex color tints the texel.
+    let atlas_uv = in.uv_rect.xy + fract(in.uv) * in.uv_rect.zw;
+    return textureSample(t_atlas, s_atlas, atlas_uv) * in.color;
 }
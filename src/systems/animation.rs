@@ -0,0 +1,94 @@
+// src/systems/animation.rs
+
+use specs::{System, Entities, Read, Write, ReadStorage, WriteStorage, Join, LendJoin};
+use crate::{
+    resources::{AnimationEvent, AnimationEvents, GameTime},
+    components::{Animation, AnimationMode, Sprite, Grounded, CharacterController, Swinging},
+};
+
+/// Below this horizontal speed a grounded character counts as standing still.
+const RUN_SPEED_THRESHOLD: f32 = 20.0;
+
+/// Picks a clip for every character from the state its controller tracks, then
+/// advances every `Animation` on game time and shows its current frame through
+/// the entity's `Sprite`.
+pub struct AnimationSystem;
+
+impl<'a> System<'a> for AnimationSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, GameTime>,
+        Write<'a, AnimationEvents>,
+        ReadStorage<'a, Grounded>,
+        ReadStorage<'a, CharacterController>,
+        ReadStorage<'a, Swinging>,
+        WriteStorage<'a, Animation>,
+        WriteStorage<'a, Sprite>,
+    );
+
+    fn run(&mut self, (entities, time, mut events, grounded, controllers, swinging, mut animations, mut sprites): Self::SystemData) {
+        events.0.clear();
+
+        // --- Clip selection ---
+        for (animation, controller, grounded, swinging) in (&mut animations, &controllers, grounded.maybe(), swinging.maybe()).join() {
+            let velocity = controller.velocity;
+            let clip = if swinging.is_some() {
+                "swing"
+            } else if grounded.is_some() {
+                if velocity.x.abs() > RUN_SPEED_THRESHOLD { "run" } else { "idle" }
+            } else if velocity.y > 0.0 {
+                "jump"
+            } else {
+                "fall"
+            };
+            animation.play(clip);
+        }
+
+        // --- Playback ---
+        for (entity, animation, sprite) in (&entities, &mut animations, &mut sprites).join() {
+            advance(entity, animation, time.dt, &mut events);
+            if let Some(frame) = animation.current_frame() {
+                if sprite.region != frame.region {
+                    sprite.region.clone_from(&frame.region);
+                }
+            }
+        }
+    }
+}
+
+/// Moves `animation` on by `dt` seconds, stepping through as many frames as that
+/// covers and raising the events of every frame it reaches.
+fn advance(entity: specs::Entity, animation: &mut Animation, dt: f32, events: &mut AnimationEvents) {
+    let Some(clip) = animation.clips.get(&animation.current) else { return };
+    if clip.frames.is_empty() {
+        return;
+    }
+
+    animation.timer += dt;
+    loop {
+        if animation.frame_entered {
+            animation.frame_entered = false;
+            if let Some(name) = &clip.frames[animation.frame].event {
+                events.0.push(AnimationEvent { entity, name: name.clone() });
+            }
+        }
+
+        // A frame with no duration would otherwise spin here forever.
+        let duration = clip.frames[animation.frame].duration.max(1e-4);
+        if animation.finished || animation.timer < duration {
+            break;
+        }
+        animation.timer -= duration;
+
+        if animation.frame + 1 < clip.frames.len() {
+            animation.frame += 1;
+        } else if clip.mode == AnimationMode::Loop {
+            animation.frame = 0;
+        } else {
+            animation.finished = true;
+            animation.timer = 0.0;
+            continue;
+        }
+        animation.frame_entered = true;
+    }
+}
@@ -0,0 +1,114 @@
+// src/systems/camera.rs
+
+use specs::{System, Read, Write, ReadStorage};
+use crate::{
+    resources::{Camera, CameraShake, GameTime, HitEffect, RenderAlpha, ScreenDimensions},
+    components::Position,
+};
+use rapier2d::na::Vector2;
+
+/// Moves the camera after its target once per frame. The target may roam inside a
+/// dead zone freely; past that the camera eases after it with critically damped
+/// smoothing, looking ahead in the direction it is moving and never leaving the
+/// level's bounds. Any trauma in `CameraShake` is then played back as screen shake,
+/// and the `HitEffect` fades on the same clock.
+pub struct CameraSystem;
+
+impl<'a> System<'a> for CameraSystem {
+    type SystemData = (
+        Write<'a, Camera>,
+        Write<'a, CameraShake>,
+        Write<'a, HitEffect>,
+        Read<'a, GameTime>,
+        Read<'a, RenderAlpha>,
+        Read<'a, ScreenDimensions>,
+        ReadStorage<'a, Position>,
+    );
+
+    fn run(&mut self, (mut camera, mut shake, mut hit, time, alpha, screen_dim, positions): Self::SystemData) {
+        let dt = if time.paused { 0.0 } else { time.real_dt * time.time_scale };
+
+        if let Some(pos) = camera.target.and_then(|target| positions.get(target)) {
+            let target = pos.interpolated(alpha.0);
+
+            // --- Dead zone ---
+            // Only drag the focus along once the target pushes against the zone's edges.
+            let mut focus = camera.focus;
+            for axis in 0..2 {
+                let offset = target[axis] - focus[axis];
+                let limit = camera.dead_zone[axis];
+                if offset > limit {
+                    focus[axis] = target[axis] - limit;
+                } else if offset < -limit {
+                    focus[axis] = target[axis] + limit;
+                }
+            }
+            camera.focus = focus;
+
+            // --- Look-ahead ---
+            let velocity = if time.dt > 0.0 { (pos.current - pos.previous) / time.dt } else { Vector2::zeros() };
+            let mut look_ahead = velocity * camera.look_ahead_time;
+            if look_ahead.norm() > camera.max_look_ahead {
+                look_ahead = look_ahead.normalize() * camera.max_look_ahead;
+            }
+
+            // --- Smoothing ---
+            let goal = focus + look_ahead;
+            let (position, velocity) = smooth_damp(camera.position, goal, camera.velocity, camera.smooth_time, dt);
+            camera.position = position;
+            camera.velocity = velocity;
+        }
+
+        // --- Level bounds ---
+        if let Some((min, max)) = camera.bounds {
+            let half = camera.half_extents(&screen_dim);
+            for axis in 0..2 {
+                // A level smaller than the screen is simply centered.
+                camera.position[axis] = if max[axis] - min[axis] <= half[axis] * 2.0 {
+                    (min[axis] + max[axis]) / 2.0
+                } else {
+                    camera.position[axis].clamp(min[axis] + half[axis], max[axis] - half[axis])
+                };
+            }
+        }
+
+        // --- Shake ---
+        // Applied on top of the clamped position, so even the level's edges shake.
+        shake.time += dt;
+        shake.trauma = (shake.trauma - shake.decay * dt).max(0.0);
+        let strength = shake.trauma * shake.trauma;
+        let t = shake.time * shake.frequency;
+        camera.shake_offset = Vector2::new(noise(t, 0.0), noise(t, 1.0)) * shake.max_offset * strength;
+        camera.shake_angle = noise(t, 2.0) * shake.max_angle * strength;
+
+        // --- Hit effect ---
+        hit.strength = (hit.strength - hit.decay * dt).max(0.0);
+    }
+}
+
+/// Smooth pseudo-random wobble in the range -1.0 to 1.0. Different `seed`s give
+/// unrelated wobbles.
+fn noise(t: f32, seed: f32) -> f32 {
+    let phase = seed * 17.13;
+    ((t + phase).sin() * 0.5 + (t * 2.31 + phase * 1.7).sin() * 0.3 + (t * 4.17 + phase * 2.9).sin() * 0.2).clamp(-1.0, 1.0)
+}
+
+/// Eases `current` towards `target` like a critically damped spring that settles in
+/// roughly `smooth_time` seconds. Returns the new position and velocity.
+fn smooth_damp(
+    current: Vector2<f32>,
+    target: Vector2<f32>,
+    velocity: Vector2<f32>,
+    smooth_time: f32,
+    dt: f32,
+) -> (Vector2<f32>, Vector2<f32>) {
+    let omega = 2.0 / smooth_time.max(1e-4);
+    let x = omega * dt;
+    // A cheap, stable approximation of e^-x.
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (velocity + change * omega) * dt;
+    let new_velocity = (velocity - temp * omega) * decay;
+    let new_position = target + (change + temp) * decay;
+    (new_position, new_velocity)
+}
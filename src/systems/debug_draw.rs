@@ -0,0 +1,197 @@
+// src/systems/debug_draw.rs
+
+use std::collections::HashMap;
+use specs::{System, Read, Write, ReadStorage, Join};
+use crate::{
+    resources::{Camera, DebugDraw, GameTime, HookshotEvent, HookshotEvents, Instance, PhysicsWorld, RenderAlpha, RenderData},
+    components::{CharacterController, PhysicsBody, Position},
+    atlas::{TextureAtlas, WHITE_REGION},
+};
+use rapier2d::prelude::*;
+use rapier2d::na::Vector2;
+
+/// How thick the overlay's lines are, in screen pixels.
+const LINE_WIDTH: f32 = 1.5;
+/// How many segments curved outlines are split into.
+const CURVE_SUBDIVISIONS: u32 = 16;
+/// How far a velocity arrow reaches, in seconds of travel at that velocity.
+const VELOCITY_SCALE: f32 = 0.15;
+/// The half-size of the crosses marking contact points and joint anchors, in screen pixels.
+const MARKER_SIZE: f32 = 4.0;
+/// How long a hookshot ray stays on screen after it is fired, in seconds.
+const RAY_LIFETIME: f32 = 1.0;
+
+const FIXED_COLOR: [f32; 4] = [0.3, 1.0, 0.4, 1.0];
+const DYNAMIC_COLOR: [f32; 4] = [0.3, 0.7, 1.0, 1.0];
+const KINEMATIC_COLOR: [f32; 4] = [1.0, 0.6, 0.2, 1.0];
+const SENSOR_COLOR: [f32; 4] = [1.0, 1.0, 0.3, 1.0];
+const VELOCITY_COLOR: [f32; 4] = [1.0, 0.3, 1.0, 1.0];
+const CONTACT_COLOR: [f32; 4] = [1.0, 0.2, 0.2, 1.0];
+const JOINT_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+const RAY_HIT_COLOR: [f32; 4] = [0.2, 1.0, 1.0, 1.0];
+const RAY_MISS_COLOR: [f32; 4] = [1.0, 0.3, 0.3, 1.0];
+
+/// Draws the physics world as an overlay while `DebugDraw` is enabled: every collider's
+/// outline, character velocities, contact points, joints, and recent hookshot rays.
+#[derive(Default)]
+pub struct DebugDrawSystem {
+    /// The tick whose hookshot events were last collected, so frames that run no tick
+    /// do not collect the same events twice.
+    last_tick: u64,
+    /// Recent hookshot rays and how long they have been shown for.
+    rays: Vec<(HookshotEvent, f32)>,
+}
+
+impl<'a> System<'a> for DebugDrawSystem {
+    type SystemData = (
+        Write<'a, RenderData>,
+        Read<'a, DebugDraw>,
+        Read<'a, PhysicsWorld>,
+        Read<'a, HookshotEvents>,
+        Read<'a, GameTime>,
+        Read<'a, Camera>,
+        Read<'a, RenderAlpha>,
+        Read<'a, TextureAtlas>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, CharacterController>,
+        ReadStorage<'a, PhysicsBody>,
+    );
+
+    fn run(&mut self, (mut render_data, debug_draw, physics, events, time, camera, alpha, atlas, positions, controllers, bodies): Self::SystemData) {
+        render_data.overlay.clear();
+
+        // Rays are remembered even while the overlay is hidden, so turning it on shows
+        // the shots that led up to it.
+        if time.tick != self.last_tick {
+            self.last_tick = time.tick;
+            self.rays.extend(events.0.iter().map(|event| (*event, 0.0)));
+        }
+        for (_, age) in &mut self.rays {
+            *age += time.real_dt;
+        }
+        self.rays.retain(|(_, age)| *age < RAY_LIFETIME);
+
+        if !debug_draw.enabled {
+            return;
+        }
+
+        let mut lines = Lines {
+            out: &mut render_data.overlay,
+            width: LINE_WIDTH / camera.zoom,
+            uv_rect: atlas.region(WHITE_REGION).uv_rect(),
+        };
+        let marker = MARKER_SIZE / camera.zoom;
+
+        // --- Collider outlines ---
+        // Sprites are drawn between the last two ticks, so a body's outline is shifted
+        // the same way to stay on top of its sprite.
+        let lag: HashMap<RigidBodyHandle, Vector2<f32>> = (&positions, &bodies).join()
+            .map(|(pos, body)| (body.rigid_body_handle, pos.interpolated(alpha.0) - pos.current))
+            .collect();
+        for (_, collider) in physics.collider_set.iter() {
+            let color = if collider.is_sensor() {
+                SENSOR_COLOR
+            } else {
+                match collider.parent().and_then(|parent| physics.rigid_body_set.get(parent)).map(|body| body.body_type()) {
+                    Some(RigidBodyType::Dynamic) => DYNAMIC_COLOR,
+                    Some(RigidBodyType::KinematicPositionBased | RigidBodyType::KinematicVelocityBased) => KINEMATIC_COLOR,
+                    _ => FIXED_COLOR,
+                }
+            };
+            let iso = collider.position();
+            let shape = collider.shape();
+            let outline = if let Some(cuboid) = shape.as_cuboid() {
+                cuboid.to_polyline()
+            } else if let Some(capsule) = shape.as_capsule() {
+                capsule.to_polyline(CURVE_SUBDIVISIONS)
+            } else if let Some(ball) = shape.as_ball() {
+                ball.to_polyline(CURVE_SUBDIVISIONS)
+            } else if let Some(polygon) = shape.as_convex_polygon() {
+                polygon.points().to_vec()
+            } else {
+                // Any other shape is at least drawn as its bounding box.
+                let aabb = shape.compute_local_aabb();
+                vec![aabb.mins, point![aabb.maxs.x, aabb.mins.y], aabb.maxs, point![aabb.mins.x, aabb.maxs.y]]
+            };
+            let offset = collider.parent().and_then(|parent| lag.get(&parent)).copied().unwrap_or_else(Vector2::zeros);
+            let points: Vec<Vector2<f32>> = outline.iter().map(|p| (iso * p).coords + offset).collect();
+            lines.loop_through(&points, color);
+        }
+
+        // --- Velocities ---
+        for (pos, controller) in (&positions, &controllers).join() {
+            let from = pos.interpolated(alpha.0);
+            lines.arrow(from, from + controller.velocity * VELOCITY_SCALE, marker * 2.0, VELOCITY_COLOR);
+        }
+
+        // --- Contacts ---
+        for pair in physics.narrow_phase.contact_pairs().filter(|pair| pair.has_any_active_contact) {
+            for manifold in &pair.manifolds {
+                for contact in &manifold.data.solver_contacts {
+                    lines.cross(contact.point.coords, marker, CONTACT_COLOR);
+                }
+            }
+        }
+
+        // --- Joints ---
+        for (_, joint) in physics.impulse_joint_set.iter() {
+            let (Some(body1), Some(body2)) = (physics.rigid_body_set.get(joint.body1), physics.rigid_body_set.get(joint.body2)) else {
+                continue;
+            };
+            let anchor1 = (body1.position() * joint.data.local_anchor1()).coords;
+            let anchor2 = (body2.position() * joint.data.local_anchor2()).coords;
+            lines.line(anchor1, anchor2, JOINT_COLOR);
+            lines.cross(anchor1, marker, JOINT_COLOR);
+            lines.cross(anchor2, marker, JOINT_COLOR);
+        }
+
+        // --- Hookshot rays ---
+        for (event, age) in &self.rays {
+            let fade = 1.0 - age / RAY_LIFETIME;
+            let (origin, end, color) = match *event {
+                HookshotEvent::Hit { origin, point, .. } => (origin, point, RAY_HIT_COLOR),
+                HookshotEvent::OutOfRange { origin, point, .. } => (origin, point, RAY_MISS_COLOR),
+                HookshotEvent::Miss { origin, direction } => (origin, origin + direction * 10_000.0, RAY_MISS_COLOR),
+            };
+            let color = [color[0], color[1], color[2], color[3] * fade];
+            lines.line(origin, end, color);
+            lines.cross(end, marker, color);
+        }
+    }
+}
+
+/// Appends lines to the overlay as thin quads.
+struct Lines<'a> {
+    out: &'a mut Vec<Instance>,
+    width: f32,
+    uv_rect: [f32; 4],
+}
+
+impl Lines<'_> {
+    fn line(&mut self, a: Vector2<f32>, b: Vector2<f32>, color: [f32; 4]) {
+        self.out.push(Instance::line(a, b, self.width, color, self.uv_rect));
+    }
+
+    /// Joins `points` with lines, closing the loop back to the first.
+    fn loop_through(&mut self, points: &[Vector2<f32>], color: [f32; 4]) {
+        for (i, &a) in points.iter().enumerate() {
+            self.line(a, points[(i + 1) % points.len()], color);
+        }
+    }
+
+    fn cross(&mut self, center: Vector2<f32>, size: f32, color: [f32; 4]) {
+        self.line(center - Vector2::new(size, size), center + Vector2::new(size, size), color);
+        self.line(center - Vector2::new(size, -size), center + Vector2::new(size, -size), color);
+    }
+
+    fn arrow(&mut self, from: Vector2<f32>, to: Vector2<f32>, head: f32, color: [f32; 4]) {
+        let Some(direction) = (to - from).try_normalize(1e-3) else {
+            return;
+        };
+        self.line(from, to, color);
+        let back = -direction * head;
+        let side = Vector2::new(-direction.y, direction.x) * head * 0.5;
+        self.line(to, to + back + side, color);
+        self.line(to, to + back - side, color);
+    }
+}
@@ -0,0 +1,46 @@
+// src/systems/footsteps.rs
+
+use specs::{System, Read, Write, ReadStorage};
+use crate::{
+    audio::Audio,
+    resources::{AnimationEvents, Camera, ParticleBursts, PhysicsWorld, ScreenDimensions},
+    components::{ParticleEffect, PhysicsBody, Position},
+    systems::player_control::feet_position,
+};
+
+/// The animation event raised as a foot touches the ground.
+const FOOTSTEP_EVENT: &str = "footstep";
+/// The sound played for it, from `assets/sounds`.
+const FOOTSTEP_SOUND: &str = "footstep";
+
+/// Kicks up a little dust under a character's feet on every footstep its
+/// animation raises, and plays a footstep sound if the character is on screen.
+pub struct FootstepSystem;
+
+impl<'a> System<'a> for FootstepSystem {
+    type SystemData = (
+        Read<'a, AnimationEvents>,
+        Read<'a, Camera>,
+        Read<'a, ScreenDimensions>,
+        Read<'a, PhysicsWorld>,
+        Write<'a, Audio>,
+        Write<'a, ParticleBursts>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, PhysicsBody>,
+    );
+
+    fn run(&mut self, (events, camera, screen, physics, mut audio, mut bursts, positions, bodies): Self::SystemData) {
+        let half = camera.half_extents(&screen);
+        for event in events.0.iter().filter(|event| event.name == FOOTSTEP_EVENT) {
+            if let Some(body) = bodies.get(event.entity) {
+                bursts.spawn(ParticleEffect::Footstep, feet_position(&physics, body), 0.0);
+            }
+
+            let Some(position) = positions.get(event.entity) else { continue };
+            let offset = position.current - camera.position;
+            if offset.x.abs() <= half.x && offset.y.abs() <= half.y {
+                audio.play(FOOTSTEP_SOUND);
+            }
+        }
+    }
+}
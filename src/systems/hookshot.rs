@@ -0,0 +1,576 @@
+// src/systems/hookshot.rs
+
+use specs::{System, Builder, Entities, LazyUpdate, Read, Write, ReadStorage, WriteStorage, Join};
+use crate::{
+    resources::{Camera, CameraShake, GameTime, InputState, PhysicsWorld, ScreenDimensions, HookshotEvent, HookshotEvents, ParticleBursts},
+    components::{Position, Rotation, Renderable, RenderLayer, ParticleEffect, ParticleEmitter, Sprite, PhysicsBody, Player, Grounded, CharacterController, Swinging, Hookshot, ReelState, ChainLink, WrapPoint,
+        CHAIN_GROUPS, GROUP_HOOKABLE, GROUP_HOOKSHOT_RAY, GROUP_LEVEL},
+    systems::player_control::{enter_swing_mode, enter_walk_mode},
+};
+use rapier2d::prelude::*;
+use rapier2d::na::Vector2;
+use winit::keyboard::KeyCode;
+
+/// How many links make up the hookshot chain, regardless of its length.
+const CHAIN_LINK_COUNT: usize = 12;
+/// The radius of each chain link's collider.
+const CHAIN_LINK_RADIUS: f32 = 3.0;
+/// Chain links are kept very light so they trail behind the player instead of dragging it down.
+const CHAIN_LINK_DENSITY: f32 = 0.05;
+/// The distance between the purely visual links laid along the wrapped part of the rope.
+const WRAPPED_LINK_SPACING: f32 = 10.0;
+
+/// How far from a corner the rope pivots, so it does not rest inside the collider.
+const WRAP_CLEARANCE: f32 = 2.0;
+/// The shortest the free, swinging part of a wrapped rope can get.
+const MIN_FREE_ROPE_LENGTH: f32 = 10.0;
+
+/// The average speed a light prop travels at when the hookshot yanks it over.
+const YANK_SPEED: f32 = 600.0;
+
+/// How much the camera shakes when the hook latches on.
+const LATCH_TRAUMA: f32 = 0.25;
+/// How much the camera shakes when the hook yanks a prop loose.
+const YANK_TRAUMA: f32 = 0.15;
+
+/// How far past the max range we keep looking. This lets us tell a target that
+/// is merely too far away apart from aiming at empty space.
+const OUT_OF_RANGE_PROBE: f32 = 4000.0;
+
+pub struct HookshotSystem;
+
+impl<'a> System<'a> for HookshotSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LazyUpdate>,
+        Read<'a, GameTime>,
+        Read<'a, InputState>,
+        Read<'a, ScreenDimensions>,
+        Read<'a, Camera>,
+        Write<'a, PhysicsWorld>,
+        Write<'a, HookshotEvents>,
+        Write<'a, CameraShake>,
+        Write<'a, ParticleBursts>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, PhysicsBody>,
+        WriteStorage<'a, Grounded>,
+        WriteStorage<'a, CharacterController>,
+        WriteStorage<'a, Swinging>,
+        WriteStorage<'a, Hookshot>,
+        WriteStorage<'a, ParticleEmitter>,
+    );
+
+    fn run(&mut self, (entities, lazy, time, input, screen_dim, camera, mut physics, mut events, mut shake, mut bursts, players, positions, bodies, mut grounded_storage, mut controllers, mut swinging, mut hookshots, mut emitters): Self::SystemData) {
+        // Events only live for the tick they were produced in.
+        events.0.clear();
+
+        let dt = time.dt;
+        let cursor_world = camera.screen_to_world(&screen_dim, input.cursor_position);
+        let reeling_in = input.pressed_keys.contains(&KeyCode::KeyW) || input.pressed_keys.contains(&KeyCode::ArrowUp);
+        let reeling_out = input.pressed_keys.contains(&KeyCode::KeyS) || input.pressed_keys.contains(&KeyCode::ArrowDown);
+
+        for (entity, _, pos, body, controller, hookshot) in (&entities, &players, &positions, &bodies, &mut controllers, &mut hookshots).join() {
+            // --- Let go of the rope ---
+            // Jumping off the rope or firing again both drop the current attachment.
+            let letting_go = input.release_pressed || input.jump_pressed || input.fire_pressed;
+            if letting_go {
+                if let Some(mut swing) = swinging.remove(entity) {
+                    detach_rope(&mut physics, &entities, &mut swing);
+                    // The hook tears loose, shedding bits of rope.
+                    bursts.spawn(ParticleEffect::Debris, swing.anchor_point, 0.0);
+                    if let Some(rb) = physics.rigid_body_set.get_mut(body.rigid_body_handle) {
+                        enter_walk_mode(rb, controller);
+                    }
+                }
+            }
+
+            // --- Reel and wrap the rope ---
+            if let Some(swing) = swinging.get_mut(entity) {
+                hookshot.reel_state = if reeling_in && !reeling_out {
+                    ReelState::ReelingIn
+                } else if reeling_out && !reeling_in {
+                    ReelState::ReelingOut
+                } else {
+                    ReelState::Idle
+                };
+                reel_rope(&mut physics, swing, hookshot, dt);
+                update_rope_wrapping(&mut physics, &entities, &lazy, swing, hookshot, body.rigid_body_handle, pos);
+            } else {
+                hookshot.reel_state = ReelState::Idle;
+            }
+
+            if !input.fire_pressed {
+                continue;
+            }
+
+            // --- Fire the hook ---
+            let origin = pos.current;
+            // Aiming at the player's own center gives no usable direction.
+            let Some(direction) = (cursor_world - origin).try_normalize(1e-6) else {
+                continue;
+            };
+
+            // The hook only sees surfaces it can stick to, so it flies through glass,
+            // ice and one-way platforms.
+            let filter = QueryFilter::new()
+                .groups(InteractionGroups::new(GROUP_HOOKSHOT_RAY, GROUP_HOOKABLE))
+                .exclude_rigid_body(body.rigid_body_handle);
+            let event = cast_hookshot(&physics, origin, direction, hookshot.range, filter);
+            log::info!("[Hookshot] {:?}", event);
+            events.0.push(event);
+
+            // --- Latch on ---
+            if let HookshotEvent::Hit { point, normal, distance, collider, .. } = event {
+                bursts.spawn(ParticleEffect::Sparks, point, normal.y.atan2(normal.x));
+
+                // A dynamic object lighter than the player gets yanked over to it. Anything
+                // heavier, like the level itself, becomes something to swing from.
+                let target = physics.collider_set.get(collider)
+                    .and_then(|c| c.parent())
+                    .filter(|handle| physics.rigid_body_set.get(*handle).is_some_and(|rb| rb.is_dynamic()));
+                if let Some(target) = target {
+                    let player_mass = physics.collider_set.get(body.collider_handle).map_or(0.0, |c| c.mass());
+                    if physics.rigid_body_set[target].mass() < player_mass {
+                        yank_toward(&mut physics, target, origin);
+                        shake.add_trauma(YANK_TRAUMA);
+                        // The yanked object splinters a little, if it knows how.
+                        let yanked = (&entities, &bodies).join().find(|(_, b)| b.rigid_body_handle == target).map(|(e, _)| e);
+                        if let Some(emitter) = yanked.and_then(|e| emitters.get_mut(e)) {
+                            emitter.trigger();
+                        }
+                        continue;
+                    }
+                }
+
+                hookshot.rope_length = distance.clamp(hookshot.min_length, hookshot.max_length);
+                let mut swing = attach_rope(&mut physics, body.rigid_body_handle, point, target, hookshot.rope_length);
+                spawn_chain(&mut physics, &entities, &lazy, &mut swing, body.rigid_body_handle, origin, hookshot.rope_length);
+                if let Some(rb) = physics.rigid_body_set.get_mut(body.rigid_body_handle) {
+                    enter_swing_mode(rb, controller);
+                }
+                grounded_storage.remove(entity);
+                swinging.insert(entity, swing).ok();
+                shake.add_trauma(LATCH_TRAUMA);
+            }
+        }
+    }
+}
+
+/// Ties the player's body to `point` with a rope joint of the given length. The rope
+/// is tied to `target` if the hook struck a dynamic body, and otherwise to a new
+/// fixed anchor body placed at `point`.
+pub fn attach_rope(
+    physics: &mut PhysicsWorld,
+    player_body: RigidBodyHandle,
+    point: Vector2<f32>,
+    target: Option<RigidBodyHandle>,
+    length: f32,
+) -> Swinging {
+    let (anchor_body, anchor_local_point, owns_anchor_body) = match target {
+        Some(target) => {
+            let local = physics.rigid_body_set[target].position().inverse_transform_point(&Point::from(point));
+            (target, local.coords, false)
+        }
+        None => {
+            let anchor = RigidBodyBuilder::fixed().translation(point).build();
+            (physics.rigid_body_set.insert(anchor), Vector2::zeros(), true)
+        }
+    };
+    let rope_joint = insert_rope_joint(physics, player_body, anchor_body, anchor_local_point, length);
+
+    Swinging {
+        anchor_point: point,
+        anchor_body,
+        anchor_local_point,
+        owns_anchor_body,
+        wrap_points: Vec::new(),
+        rope_joint,
+        chain_links: Vec::new(),
+        chain_joints: Vec::new(),
+        wrapped_links: Vec::new(),
+    }
+}
+
+fn insert_rope_joint(
+    physics: &mut PhysicsWorld,
+    player_body: RigidBodyHandle,
+    anchor_body: RigidBodyHandle,
+    anchor_local_point: Vector2<f32>,
+    length: f32,
+) -> ImpulseJointHandle {
+    let joint = RopeJointBuilder::new(length)
+        .local_anchor1(Point::origin())
+        .local_anchor2(Point::from(anchor_local_point));
+    physics.impulse_joint_set.insert(player_body, anchor_body, joint, true)
+}
+
+/// Launches `target` on an arc that lands it at `destination`, arriving sooner the
+/// closer it already is.
+fn yank_toward(physics: &mut PhysicsWorld, target: RigidBodyHandle, destination: Vector2<f32>) {
+    let gravity = physics.gravity;
+    let Some(rb) = physics.rigid_body_set.get_mut(target) else {
+        return;
+    };
+    let offset = destination - rb.translation();
+    let flight_time = (offset.norm() / YANK_SPEED).max(0.05);
+    // Solve `offset = v * t + g * t^2 / 2` for the launch velocity `v`.
+    let launch_velocity = offset / flight_time - gravity * flight_time / 2.0;
+    let impulse = (launch_velocity - rb.linvel()) * rb.mass();
+    rb.apply_impulse(impulse, true);
+}
+
+/// Removes a rigid body along with its colliders and any joints attached to it.
+fn remove_body(pw: &mut PhysicsWorld, handle: RigidBodyHandle) {
+    pw.rigid_body_set.remove(
+        handle,
+        &mut pw.island_manager,
+        &mut pw.collider_set,
+        &mut pw.impulse_joint_set,
+        &mut pw.multibody_joint_set,
+        true,
+    );
+}
+
+/// Returns the local anchors of the `index`th chain joint for links spaced `spacing` apart.
+/// Joint 0 ties the player to the first link and the last joint ties the last link to the
+/// rope's anchor. Those two ends are `None`, since they do not depend on the spacing.
+fn chain_joint_anchors(index: usize, spacing: f32) -> (Option<Point<f32>>, Option<Point<f32>>) {
+    let anchor1 = (index > 0).then(|| point![spacing / 2.0, 0.0]);
+    let anchor2 = (index < CHAIN_LINK_COUNT).then(|| point![-spacing / 2.0, 0.0]);
+    (anchor1, anchor2)
+}
+
+fn chain_link_renderable() -> Renderable {
+    Renderable { color: [0.6, 0.6, 0.65, 1.0], width: CHAIN_LINK_RADIUS * 2.0, height: CHAIN_LINK_RADIUS * 2.0, layer: RenderLayer::Props }
+}
+
+/// Spawns a chain of the given length between `from` (the player) and the active
+/// anchor, tied together with revolute joints. A purely visual chain is also laid
+/// along any wrapped part of the rope. Everything spawned is recorded on `swing`
+/// so `despawn_chain` can clean it up.
+pub fn spawn_chain(
+    physics: &mut PhysicsWorld,
+    entities: &Entities,
+    lazy: &LazyUpdate,
+    swing: &mut Swinging,
+    player_body: RigidBodyHandle,
+    from: Vector2<f32>,
+    length: f32,
+) {
+    let pw = physics;
+
+    // --- Wrapped part of the rope ---
+    let mut segment_start = swing.anchor_point;
+    for wrap in &swing.wrap_points {
+        let segment = wrap.point - segment_start;
+        let count = (segment.norm() / WRAPPED_LINK_SPACING).ceil().max(1.0) as usize;
+        // Lie the links along the rope, as the swinging ones would hang.
+        let angle = segment.y.atan2(segment.x);
+        for i in 0..count {
+            let center = segment_start + segment * ((i as f32 + 0.5) / count as f32);
+            let link = lazy.create_entity(entities)
+                .with(Position::new(center))
+                .with(Rotation::new(angle))
+                .with(chain_link_renderable())
+                .with(Sprite::new("chain_link"))
+                .with(ChainLink)
+                .build();
+            swing.wrapped_links.push(link);
+        }
+        segment_start = wrap.point;
+    }
+
+    // --- Swinging part of the rope ---
+    let to = swing.active_anchor_point();
+    let spacing = length / CHAIN_LINK_COUNT as f32;
+    let Some(direction) = (to - from).try_normalize(1e-6) else {
+        return;
+    };
+    // Links are laid along their local x axis, so rotate them to face the anchor.
+    let angle = direction.y.atan2(direction.x);
+    let step = (to - from).norm() / CHAIN_LINK_COUNT as f32;
+
+    let mut previous_body = player_body;
+    for i in 0..CHAIN_LINK_COUNT {
+        let center = from + direction * step * (i as f32 + 0.5);
+
+        let rigid_body = RigidBodyBuilder::dynamic()
+            .translation(center)
+            .rotation(angle)
+            .build();
+        let collider = ColliderBuilder::ball(CHAIN_LINK_RADIUS)
+            .density(CHAIN_LINK_DENSITY)
+            .collision_groups(CHAIN_GROUPS)
+            .build();
+        let rb_handle = pw.rigid_body_set.insert(rigid_body);
+        let col_handle = pw.collider_set.insert_with_parent(collider, rb_handle, &mut pw.rigid_body_set);
+
+        let (anchor1, anchor2) = chain_joint_anchors(i, spacing);
+        let joint = RevoluteJointBuilder::new()
+            .local_anchor1(anchor1.unwrap_or_else(Point::origin))
+            .local_anchor2(anchor2.unwrap_or_else(Point::origin));
+        swing.chain_joints.push(pw.impulse_joint_set.insert(previous_body, rb_handle, joint, true));
+
+        let link = lazy.create_entity(entities)
+            .with(Position::new(center))
+            .with(Rotation::new(angle))
+            .with(chain_link_renderable())
+            .with(Sprite::new("chain_link"))
+            .with(PhysicsBody { rigid_body_handle: rb_handle, collider_handle: col_handle })
+            .with(ChainLink)
+            .build();
+        swing.chain_links.push((link, rb_handle));
+        previous_body = rb_handle;
+    }
+
+    let (anchor1, _) = chain_joint_anchors(CHAIN_LINK_COUNT, spacing);
+    let joint = RevoluteJointBuilder::new()
+        .local_anchor1(anchor1.unwrap_or_else(Point::origin))
+        .local_anchor2(Point::from(swing.active_anchor_local_point()));
+    let anchor_body = swing.active_anchor_body();
+    swing.chain_joints.push(pw.impulse_joint_set.insert(previous_body, anchor_body, joint, true));
+}
+
+/// Removes every chain link body, collider and joint from the physics world and
+/// deletes the chain link entities.
+pub fn despawn_chain(pw: &mut PhysicsWorld, entities: &Entities, swing: &mut Swinging) {
+    for joint in swing.chain_joints.drain(..) {
+        pw.impulse_joint_set.remove(joint, true);
+    }
+    for (link, rb_handle) in swing.chain_links.drain(..) {
+        remove_body(pw, rb_handle);
+        entities.delete(link).ok();
+    }
+    for link in swing.wrapped_links.drain(..) {
+        entities.delete(link).ok();
+    }
+}
+
+/// Respaces the chain links so the chain matches a rope of the given length.
+fn resize_chain(physics: &mut PhysicsWorld, swing: &Swinging, length: f32) {
+    let spacing = length / CHAIN_LINK_COUNT as f32;
+    for (i, handle) in swing.chain_joints.iter().enumerate() {
+        if let Some(joint) = physics.impulse_joint_set.get_mut(*handle) {
+            let (anchor1, anchor2) = chain_joint_anchors(i, spacing);
+            if let Some(anchor1) = anchor1 {
+                joint.data.set_local_anchor1(anchor1);
+            }
+            if let Some(anchor2) = anchor2 {
+                joint.data.set_local_anchor2(anchor2);
+            }
+        }
+    }
+}
+
+/// Shortens or lengthens the rope according to the hookshot's reel state,
+/// keeping it within the hookshot's configured limits. Only the free part of the
+/// rope past the last wrap point can be reeled in.
+pub fn reel_rope(physics: &mut PhysicsWorld, swing: &Swinging, hookshot: &mut Hookshot, dt: f32) {
+    let delta = match hookshot.reel_state {
+        ReelState::Idle => return,
+        ReelState::ReelingIn => -hookshot.reel_speed * dt,
+        ReelState::ReelingOut => hookshot.reel_speed * dt,
+    };
+    let wrapped_length = swing.wrapped_length();
+    let min_length = hookshot.min_length.max(wrapped_length + MIN_FREE_ROPE_LENGTH);
+    hookshot.rope_length = (hookshot.rope_length + delta).clamp(min_length, hookshot.max_length.max(min_length));
+    let free_length = hookshot.rope_length - wrapped_length;
+
+    let Some(joint) = physics.impulse_joint_set.get_mut(swing.rope_joint) else {
+        return;
+    };
+    joint.data.set_limits(JointAxis::X, [0.0, free_length]);
+
+    // A sleeping player body would not notice its rope getting shorter.
+    let player_body = joint.body1;
+    if let Some(rb) = physics.rigid_body_set.get_mut(player_body) {
+        rb.wake_up(true);
+    }
+
+    resize_chain(physics, swing, free_length);
+}
+
+/// Picks the corner of `collider` that the rope segment from `from` to `to` has
+/// caught on, preferring corners on the side the rope swept in from. The corner is
+/// nudged outwards so the rope can pivot around it without touching the collider.
+fn wrap_corner(collider: &Collider, from: Vector2<f32>, to: Vector2<f32>, swept_from: Vector2<f32>) -> Option<Vector2<f32>> {
+    let (corners, center) = match collider.shape().as_cuboid() {
+        Some(cuboid) => {
+            let he = cuboid.half_extents;
+            let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .map(|(sx, sy)| (collider.position() * point![sx * he.x, sy * he.y]).coords);
+            (corners, collider.position().translation.vector)
+        }
+        None => {
+            let aabb = collider.compute_aabb();
+            (aabb.vertices().map(|v| v.coords), aabb.center().coords)
+        }
+    };
+
+    let segment = to - from;
+    let length_sq = segment.norm_squared();
+    if length_sq < 1e-6 {
+        return None;
+    }
+    let swept_side = segment.perp(&(swept_from - from)).signum();
+
+    // Only corners lying alongside the segment can catch the rope. Among those, the
+    // closest one to the segment on the swept side is the one the rope bent around.
+    corners
+        .iter()
+        .filter(|corner| {
+            let t = (*corner - from).dot(&segment) / length_sq;
+            t > 0.0 && t < 1.0
+        })
+        .map(|corner| {
+            let side = segment.perp(&(corner - from));
+            let penalty = if side.signum() == swept_side { 0.0 } else { f32::MAX / 2.0 };
+            (corner, side.abs() + penalty)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(corner, _)| {
+            let outwards = (corner - center).try_normalize(1e-6).unwrap_or_else(Vector2::zeros);
+            corner + outwards * WRAP_CLEARANCE
+        })
+}
+
+/// Bends the rope around level geometry that has come between the player and the
+/// active anchor, and straightens it again once the player swings back. When the
+/// wrapping changes, the rope joint is re-tied to the new active anchor and the
+/// chain is rebuilt to match.
+pub fn update_rope_wrapping(
+    physics: &mut PhysicsWorld,
+    entities: &Entities,
+    lazy: &LazyUpdate,
+    swing: &mut Swinging,
+    hookshot: &mut Hookshot,
+    player_body: RigidBodyHandle,
+    player_position: &Position,
+) {
+    let swept_from = player_position.previous;
+    let player_position = player_position.current;
+    let mut changed = false;
+
+    // A hook stuck in a prop moves along with it.
+    if !swing.owns_anchor_body {
+        if let Some(rb) = physics.rigid_body_set.get(swing.anchor_body) {
+            swing.anchor_point = rb.position().transform_point(&Point::from(swing.anchor_local_point)).coords;
+        }
+    }
+
+    // --- Unwrap ---
+    // Once the rope bends the other way around the innermost corner, it no longer
+    // touches it and that part of the rope swings freely again.
+    while let Some(wrap) = swing.wrap_points.last().copied() {
+        let previous = swing.wrap_points.iter().rev().nth(1).map_or(swing.anchor_point, |w| w.point);
+        let bend = (wrap.point - previous).perp(&(player_position - wrap.point));
+        if bend * wrap.winding >= 0.0 {
+            break;
+        }
+        swing.wrap_points.pop();
+        remove_body(physics, wrap.body);
+        changed = true;
+    }
+
+    // --- Wrap ---
+    // Only solid level geometry wraps the rope, whether or not the hook could stick to it.
+    let anchor = swing.active_anchor_point();
+    let to_anchor = anchor - player_position;
+    let distance = to_anchor.norm();
+    if !changed && distance > WRAP_CLEARANCE * 2.0 {
+        let ray = Ray::new(point![player_position.x, player_position.y], to_anchor / distance);
+        let filter = QueryFilter::exclude_dynamic()
+            .groups(InteractionGroups::new(GROUP_HOOKSHOT_RAY, GROUP_LEVEL))
+            .exclude_rigid_body(player_body);
+        let hit = physics.query_pipeline.cast_ray(
+            &physics.rigid_body_set,
+            &physics.collider_set,
+            &ray,
+            distance - WRAP_CLEARANCE,
+            true,
+            filter,
+        );
+
+        let corner = hit
+            .and_then(|(handle, _)| physics.collider_set.get(handle))
+            .and_then(|collider| wrap_corner(collider, anchor, player_position, swept_from));
+        if let Some(corner) = corner {
+            if (corner - anchor).norm() > WRAP_CLEARANCE {
+                let body = physics.rigid_body_set.insert(RigidBodyBuilder::fixed().translation(corner).build());
+                let winding = (corner - anchor).perp(&(player_position - corner)).signum();
+                swing.wrap_points.push(WrapPoint { point: corner, body, winding });
+                changed = true;
+            }
+        }
+    }
+
+    if !changed {
+        return;
+    }
+
+    // --- Re-tie the rope to the new active anchor ---
+    let wrapped_length = swing.wrapped_length();
+    hookshot.rope_length = hookshot.rope_length.max(wrapped_length + MIN_FREE_ROPE_LENGTH);
+    let free_length = hookshot.rope_length - wrapped_length;
+
+    physics.impulse_joint_set.remove(swing.rope_joint, true);
+    swing.rope_joint = insert_rope_joint(physics, player_body, swing.active_anchor_body(), swing.active_anchor_local_point(), free_length);
+
+    despawn_chain(physics, entities, swing);
+    spawn_chain(physics, entities, lazy, swing, player_body, player_position, free_length);
+}
+
+/// Removes the rope joint, its wrap bodies, the whole chain and, unless the hook was
+/// stuck in a prop, its anchor body from the physics world. Also deletes the chain
+/// link entities.
+pub fn detach_rope(pw: &mut PhysicsWorld, entities: &Entities, swing: &mut Swinging) {
+    despawn_chain(pw, entities, swing);
+
+    pw.impulse_joint_set.remove(swing.rope_joint, true);
+    for wrap in swing.wrap_points.drain(..) {
+        remove_body(pw, wrap.body);
+    }
+    if swing.owns_anchor_body {
+        remove_body(pw, swing.anchor_body);
+    }
+}
+
+/// Casts the hookshot ray from `origin` along the normalized `direction` and
+/// classifies the result as a hit, a miss or an out-of-range target.
+pub fn cast_hookshot(
+    physics: &PhysicsWorld,
+    origin: Vector2<f32>,
+    direction: Vector2<f32>,
+    max_range: f32,
+    filter: QueryFilter,
+) -> HookshotEvent {
+    let ray = Ray::new(point![origin.x, origin.y], direction);
+    let hit = physics.query_pipeline.cast_ray_and_get_normal(
+        &physics.rigid_body_set,
+        &physics.collider_set,
+        &ray,
+        max_range.max(OUT_OF_RANGE_PROBE),
+        true,
+        filter,
+    );
+
+    match hit {
+        Some((collider, intersection)) if intersection.time_of_impact <= max_range => {
+            let point = ray.point_at(intersection.time_of_impact);
+            HookshotEvent::Hit {
+                origin,
+                point: point.coords,
+                normal: intersection.normal,
+                collider,
+                distance: intersection.time_of_impact,
+            }
+        }
+        Some((_, intersection)) => HookshotEvent::OutOfRange {
+            origin,
+            point: ray.point_at(intersection.time_of_impact).coords,
+            distance: intersection.time_of_impact,
+        },
+        None => HookshotEvent::Miss { origin, direction },
+    }
+}
@@ -0,0 +1,116 @@
+// src/systems/hud.rs
+
+use specs::{System, Read, Write, ReadStorage, Join};
+use crate::{
+    resources::{DebugDraw, GameTime, HookshotEvent, HookshotEvents, Menu, MenuScreen, Particles, ScreenDimensions, UiDrawList},
+    components::{Hookshot, Player},
+    font,
+};
+use rapier2d::na::Vector2;
+
+/// How tall the HUD's text is, in pixels.
+const TEXT_SIZE: f32 = 20.0;
+/// How far the HUD keeps from the edges of the window, in pixels.
+const MARGIN: f32 = 12.0;
+/// How quickly the frame rate readout follows the real frame rate. Smaller is steadier.
+const FPS_SMOOTHING: f32 = 0.1;
+/// How long the out of range notice stays up, in seconds.
+const OUT_OF_RANGE_LIFETIME: f32 = 1.0;
+
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const SLOW_MOTION_COLOR: [f32; 4] = [0.5, 0.8, 1.0, 1.0];
+const DEBUG_COLOR: [f32; 4] = [0.6, 1.0, 0.6, 1.0];
+const OUT_OF_RANGE_COLOR: [f32; 4] = [1.0, 0.55, 0.4, 1.0];
+const SHADE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+
+/// Queues the in-game HUD onto the UI layer: the speedrun timer, the pause and slow
+/// motion indicators, a notice when the hookshot falls short, and frame statistics
+/// while the debug overlay is on.
+#[derive(Default)]
+pub struct HudSystem {
+    /// The smoothed frame rate shown by the debug statistics.
+    fps: f32,
+    /// The tick whose hookshot events were last read.
+    last_tick: u64,
+    /// How far the last shot that fell short would have had to reach, and how long
+    /// its notice has been shown for.
+    out_of_range: Option<(f32, f32)>,
+}
+
+impl<'a> System<'a> for HudSystem {
+    type SystemData = (
+        Write<'a, UiDrawList>,
+        Read<'a, GameTime>,
+        Read<'a, DebugDraw>,
+        Read<'a, Menu>,
+        Read<'a, Particles>,
+        Read<'a, ScreenDimensions>,
+        Read<'a, HookshotEvents>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Hookshot>,
+    );
+
+    fn run(&mut self, (mut ui, time, debug_draw, menu, particles, screen, events, players, hookshots): Self::SystemData) {
+        if time.real_dt > 0.0 {
+            let fps = 1.0 / time.real_dt;
+            // Start from the first frame's rate rather than climbing up from zero.
+            self.fps = if self.fps == 0.0 { fps } else { self.fps + (fps - self.fps) * FPS_SMOOTHING };
+        }
+
+        // --- Timer ---
+        let timer = format_time(time.elapsed);
+        let timer_size = font::measure_text(TEXT_SIZE, &timer);
+        ui.draw_rect(Vector2::new(MARGIN - 4.0, MARGIN - 2.0), Vector2::new(timer_size[0] + 8.0, timer_size[1] + 4.0), SHADE_COLOR);
+        ui.draw_text(Vector2::new(MARGIN, MARGIN), TEXT_SIZE, TEXT_COLOR, &timer);
+        if time.time_scale < 1.0 {
+            let label = format!("SLOW-MO x{:.2}", time.time_scale);
+            ui.draw_text(Vector2::new(MARGIN, MARGIN + TEXT_SIZE + 4.0), TEXT_SIZE, SLOW_MOTION_COLOR, &label);
+        }
+
+        // --- Out of range ---
+        if time.tick != self.last_tick {
+            self.last_tick = time.tick;
+            for event in &events.0 {
+                if let HookshotEvent::OutOfRange { distance, .. } = *event {
+                    self.out_of_range = Some((distance, 0.0));
+                }
+            }
+        }
+        if let Some((distance, age)) = &mut self.out_of_range {
+            *age += time.real_dt;
+            if *age < OUT_OF_RANGE_LIFETIME {
+                let range = (&players, &hookshots).join().next().map_or(0.0, |(_, hookshot)| hookshot.range);
+                let label = format!("OUT OF RANGE {:.0} / {:.0}", distance, range);
+                let extent = font::measure_text(TEXT_SIZE, &label);
+                let fade = 1.0 - *age / OUT_OF_RANGE_LIFETIME;
+                let color = [OUT_OF_RANGE_COLOR[0], OUT_OF_RANGE_COLOR[1], OUT_OF_RANGE_COLOR[2], OUT_OF_RANGE_COLOR[3] * fade];
+                ui.draw_text(Vector2::new((screen.width - extent[0]) / 2.0, screen.height - extent[1] - MARGIN), TEXT_SIZE, color, &label);
+            } else {
+                self.out_of_range = None;
+            }
+        }
+
+        // --- Pause ---
+        // The pause menu says so itself.
+        if time.paused && menu.screen == MenuScreen::Closed {
+            ui.draw_rect(Vector2::zeros(), Vector2::new(screen.width, screen.height), SHADE_COLOR);
+            let size = TEXT_SIZE * 2.0;
+            let extent = font::measure_text(size, "PAUSED");
+            let pos = Vector2::new(screen.width - extent[0], screen.height - extent[1]) / 2.0;
+            ui.draw_text(pos, size, TEXT_COLOR, "PAUSED");
+        }
+
+        // --- Debug statistics ---
+        if debug_draw.enabled {
+            let stats = format!("{:>5.0} fps\n{:>5} particles\n{:>5} ticks", self.fps, particles.0.len(), time.tick);
+            let extent = font::measure_text(TEXT_SIZE, &stats);
+            ui.draw_text(Vector2::new(screen.width - extent[0] - MARGIN, MARGIN), TEXT_SIZE, DEBUG_COLOR, &stats);
+        }
+    }
+}
+
+/// Formats a duration in seconds as a speedrun timer, `MM:SS.cc`.
+fn format_time(seconds: f32) -> String {
+    let centiseconds = (seconds.max(0.0) * 100.0) as u64;
+    format!("{:02}:{:02}.{:02}", centiseconds / 6000, centiseconds / 100 % 60, centiseconds % 100)
+}
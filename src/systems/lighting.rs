@@ -0,0 +1,144 @@
+// src/systems/lighting.rs
+
+use specs::{System, Read, Write, ReadStorage, Join};
+use crate::{
+    resources::{Camera, LightData, LightDraw, LightVertex, PhysicsWorld, RenderAlpha, ScreenDimensions},
+    components::{Light, Position, ShadowMode},
+    color,
+};
+use rapier2d::prelude::*;
+use rapier2d::na::Vector2;
+
+/// How many points across a soft light's source each cast their own shadow. More
+/// samples give smoother penumbras at the cost of more draws.
+const SOFT_SHADOW_SAMPLES: usize = 6;
+/// How far past a shadow-casting edge its shadow volume reaches, in light radii.
+/// Far enough that the volume's far end never cuts back into the light.
+const SHADOW_LENGTH: f32 = 50.0;
+
+/// Builds the lighting pass's geometry for every light in view: a quad for the light
+/// itself and, for lights that cast shadows, volumes extruded away from the light
+/// behind every static cuboid collider it reaches.
+pub struct LightingSystem;
+
+impl<'a> System<'a> for LightingSystem {
+    type SystemData = (
+        Write<'a, LightData>,
+        Read<'a, PhysicsWorld>,
+        Read<'a, Camera>,
+        Read<'a, ScreenDimensions>,
+        Read<'a, RenderAlpha>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Light>,
+    );
+
+    fn run(&mut self, (mut light_data, physics, camera, screen, alpha, positions, lights): Self::SystemData) {
+        let light_data = &mut *light_data;
+        light_data.vertices.clear();
+        light_data.draws.clear();
+
+        // --- Shadow casters ---
+        // Each static cuboid as its four corners, counter-clockwise, in world space.
+        let casters: Vec<[Vector2<f32>; 4]> = physics.collider_set.iter()
+            .filter(|(_, collider)| !collider.is_sensor())
+            .filter(|(_, collider)| collider.parent()
+                .and_then(|parent| physics.rigid_body_set.get(parent))
+                .is_none_or(|body| body.is_fixed()))
+            .filter_map(|(_, collider)| {
+                let half = collider.shape().as_cuboid()?.half_extents;
+                let iso = collider.position();
+                let corner = |x: f32, y: f32| (iso * Point::new(x, y)).coords;
+                Some([corner(-half.x, -half.y), corner(half.x, -half.y), corner(half.x, half.y), corner(-half.x, half.y)])
+            })
+            .collect();
+
+        let view_radius = camera.half_extents(&screen).norm();
+        for (pos, light) in (&positions, &lights).join() {
+            let center = pos.interpolated(alpha.0);
+            // Skip lights that cannot reach the screen.
+            if (center - camera.position).norm() > view_radius + light.radius {
+                continue;
+            }
+
+            let samples: Vec<Vector2<f32>> = match light.shadows {
+                ShadowMode::None | ShadowMode::Hard => vec![center],
+                ShadowMode::Soft => (0..SOFT_SHADOW_SAMPLES).map(|i| center + disc_sample(i, SOFT_SHADOW_SAMPLES) * light.source_radius).collect(),
+            };
+            // The samples share the light between them, so together they are as bright as one.
+            let strength = light.intensity / samples.len() as f32;
+            let linear = color::to_linear(light.color);
+            let color = [linear[0] * strength, linear[1] * strength, linear[2] * strength, 1.0];
+
+            for source in samples {
+                let shadows_start = light_data.vertices.len() as u32;
+                if light.shadows != ShadowMode::None {
+                    for corners in &casters {
+                        push_shadow_volume(&mut light_data.vertices, corners, source, light.radius);
+                    }
+                }
+                let light_start = light_data.vertices.len() as u32;
+                push_light_quad(&mut light_data.vertices, center, light.radius, color);
+                light_data.draws.push(LightDraw {
+                    shadows: shadows_start..light_start,
+                    light: light_start..light_data.vertices.len() as u32,
+                });
+            }
+        }
+    }
+}
+
+/// Spreads sample `i` of `count` evenly over the unit disc.
+fn disc_sample(i: usize, count: usize) -> Vector2<f32> {
+    // A golden-angle spiral covers the disc evenly for any sample count.
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+    let radius = ((i as f32 + 0.5) / count as f32).sqrt();
+    let angle = i as f32 * golden_angle;
+    Vector2::new(angle.cos(), angle.sin()) * radius
+}
+
+/// Adds the shadow that the cuboid with `corners` casts from a light at `source`.
+/// The shadow is the union of the volumes behind the edges facing away from the light.
+fn push_shadow_volume(vertices: &mut Vec<LightVertex>, corners: &[Vector2<f32>; 4], source: Vector2<f32>, radius: f32) {
+    // Skip casters entirely out of the light's reach.
+    let (min, max) = corners.iter().fold(
+        (corners[0], corners[0]),
+        |(min, max), corner| (min.inf(corner), max.sup(corner)),
+    );
+    let nearest = source.sup(&min).inf(&max);
+    if (nearest - source).norm() > radius {
+        return;
+    }
+    // A light inside a wall would shadow everything; let it shine instead.
+    if nearest == source {
+        return;
+    }
+
+    let vertex = |position: Vector2<f32>| LightVertex { position: position.into(), local: [0.0, 0.0], color: [0.0; 4] };
+    let length = radius * SHADOW_LENGTH;
+    for i in 0..4 {
+        let a = corners[i];
+        let b = corners[(i + 1) % 4];
+        let edge = b - a;
+        // The corners run counter-clockwise, so this normal points out of the cuboid.
+        let normal = Vector2::new(edge.y, -edge.x);
+        if normal.dot(&((a + b) / 2.0 - source)) <= 0.0 {
+            continue;
+        }
+        let far_a = a + (a - source).normalize() * length;
+        let far_b = b + (b - source).normalize() * length;
+        vertices.extend_from_slice(&[vertex(a), vertex(b), vertex(far_b), vertex(a), vertex(far_b), vertex(far_a)]);
+    }
+}
+
+/// Adds the quad a light is drawn with, covering its whole radius.
+fn push_light_quad(vertices: &mut Vec<LightVertex>, center: Vector2<f32>, radius: f32, color: [f32; 4]) {
+    let vertex = |x: f32, y: f32| LightVertex {
+        position: [center.x + x * radius, center.y + y * radius],
+        local: [x, y],
+        color,
+    };
+    vertices.extend_from_slice(&[
+        vertex(-1.0, -1.0), vertex(1.0, -1.0), vertex(1.0, 1.0),
+        vertex(-1.0, -1.0), vertex(1.0, 1.0), vertex(-1.0, 1.0),
+    ]);
+}
@@ -0,0 +1,121 @@
+// src/systems/menu.rs
+
+use specs::{System, Read, Write};
+use crate::{
+    resources::{DebugDraw, GameTime, GraphicsSettings, InputState, Menu, MenuScreen, PresentMode, ScreenDimensions, UiDrawList},
+    ui::{Ui, UiState},
+};
+use rapier2d::na::Vector2;
+
+/// How wide the menu's panel is, in pixels.
+const PANEL_WIDTH: f32 = 360.0;
+/// How far down the window the panel starts, as a fraction of the window's height.
+const PANEL_TOP: f32 = 0.25;
+
+const TITLE_COLOR: [f32; 4] = [1.0, 0.8, 0.3, 1.0];
+const HEADING_COLOR: [f32; 4] = [0.7, 0.7, 0.75, 1.0];
+
+/// Runs the in-game menu. Escape opens the pause menu, which pauses the game, and
+/// steps back out of whichever screen is showing.
+#[derive(Default)]
+pub struct MenuSystem {
+    ui: UiState,
+    /// Whether the game was already paused when the menu opened, so closing the
+    /// menu leaves it that way.
+    was_paused: bool,
+}
+
+impl<'a> System<'a> for MenuSystem {
+    type SystemData = (
+        Write<'a, Menu>,
+        Write<'a, UiDrawList>,
+        Write<'a, GameTime>,
+        Write<'a, DebugDraw>,
+        Write<'a, GraphicsSettings>,
+        Write<'a, InputState>,
+        Read<'a, ScreenDimensions>,
+    );
+
+    fn run(&mut self, (mut menu, mut draw_list, mut time, mut debug_draw, mut graphics, mut input, screen): Self::SystemData) {
+        let screen_before = menu.screen;
+        if input.menu.back {
+            menu.screen = match menu.screen {
+                MenuScreen::Closed => MenuScreen::Pause,
+                MenuScreen::Pause => MenuScreen::Closed,
+                MenuScreen::Options => MenuScreen::Pause,
+                MenuScreen::Graphics => MenuScreen::Options,
+            };
+        }
+
+        if menu.screen != MenuScreen::Closed {
+            let origin = Vector2::new((screen.width - PANEL_WIDTH) / 2.0, screen.height * PANEL_TOP);
+            let mut ui = Ui::begin(&mut self.ui, &input, &mut draw_list, origin, PANEL_WIDTH);
+            match menu.screen {
+                MenuScreen::Pause => {
+                    ui.label("PAUSED", TITLE_COLOR);
+                    if ui.button("Resume") {
+                        menu.screen = MenuScreen::Closed;
+                    }
+                    if ui.button("Options") {
+                        menu.screen = MenuScreen::Options;
+                    }
+                    if ui.button("Quit") {
+                        menu.quit_requested = true;
+                    }
+                }
+                MenuScreen::Options => {
+                    ui.label("OPTIONS", TITLE_COLOR);
+                    ui.toggle("Debug overlay", &mut debug_draw.enabled);
+                    let mut speed = time.time_scale;
+                    if ui.slider("Game speed", &mut speed, 0.1..=1.0, 0.05) {
+                        time.set_time_scale(speed);
+                    }
+                    if ui.button("Graphics") {
+                        menu.screen = MenuScreen::Graphics;
+                    }
+                    if ui.button("Back") {
+                        menu.screen = MenuScreen::Pause;
+                    }
+                }
+                MenuScreen::Graphics => {
+                    ui.label("GRAPHICS", TITLE_COLOR);
+                    ui.toggle("Bloom", &mut graphics.bloom);
+                    ui.toggle("Vignette", &mut graphics.vignette);
+                    ui.toggle("Hit aberration", &mut graphics.chromatic_aberration);
+                    ui.toggle("Color grading", &mut graphics.color_grading);
+                    ui.label("Present mode", HEADING_COLOR);
+                    let names = PresentMode::ALL.map(PresentMode::name);
+                    let mut selected = PresentMode::ALL.iter().position(|&mode| mode == graphics.present_mode).unwrap_or(0);
+                    if ui.list(&names, &mut selected) {
+                        graphics.present_mode = PresentMode::ALL[selected];
+                    }
+                    if ui.button("Back") {
+                        menu.screen = MenuScreen::Options;
+                    }
+                }
+                MenuScreen::Closed => {}
+            }
+            ui.end();
+        }
+
+        if menu.screen != screen_before {
+            // Each screen starts with its first widget focused.
+            self.ui.reset();
+            match (screen_before, menu.screen) {
+                (MenuScreen::Closed, _) => {
+                    self.was_paused = time.paused;
+                    time.paused = true;
+                }
+                (_, MenuScreen::Closed) => time.paused = self.was_paused,
+                _ => {}
+            }
+        }
+
+        // Confirming or clicking through the menu, including the press that closes
+        // it, must not also jump or fire once the game resumes.
+        if menu.screen != MenuScreen::Closed || screen_before != MenuScreen::Closed {
+            input.jump_pressed = false;
+            input.fire_pressed = false;
+        }
+    }
+}
@@ -2,6 +2,16 @@
 
 // This file makes the other files in this directory available as modules
 // to the rest of the application.
+pub mod animation;
+pub mod camera;
+pub mod debug_draw;
+pub mod footsteps;
+pub mod hookshot;
+pub mod hud;
+pub mod lighting;
+pub mod menu;
+pub mod particles;
 pub mod physics;
 pub mod player_control;
 pub mod rendering;
+pub mod ui;
@@ -0,0 +1,103 @@
+// src/systems/particles.rs
+
+use specs::{System, Read, Write, ReadStorage, WriteStorage, Join};
+use crate::{
+    resources::{GameTime, Particle, ParticleBursts, Particles, PhysicsWorld},
+    components::{ParticleEmitter, ParticleSettings, Position},
+};
+use rapier2d::na::Vector2;
+
+/// The most particles alive at once. Past this, new particles are simply not spawned.
+const MAX_PARTICLES: usize = 4096;
+
+/// Spawns particles from emitters and requested bursts, then moves and ages every
+/// live particle, dropping those that have outlived their lifetimes.
+pub struct ParticleSystem {
+    /// The state of a small xorshift generator. Particles only need to look random.
+    seed: u32,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self { seed: 0x9e37_79b9 }
+    }
+}
+
+impl<'a> System<'a> for ParticleSystem {
+    type SystemData = (
+        Read<'a, GameTime>,
+        Read<'a, PhysicsWorld>,
+        Write<'a, Particles>,
+        Write<'a, ParticleBursts>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, ParticleEmitter>,
+    );
+
+    fn run(&mut self, (time, physics, mut particles, mut bursts, positions, mut emitters): Self::SystemData) {
+        let dt = time.dt;
+
+        // --- Move and age ---
+        let gravity = physics.gravity;
+        particles.0.retain_mut(|particle| {
+            particle.age += dt;
+            particle.previous = particle.position;
+            particle.velocity += gravity * particle.settings.gravity_scale * dt;
+            particle.position += particle.velocity * dt;
+            particle.age < particle.lifetime
+        });
+
+        // --- Emitters ---
+        for (pos, emitter) in (&positions, &mut emitters).join() {
+            let mut count = emitter.pending_bursts * emitter.burst_count;
+            emitter.pending_bursts = 0;
+            if emitter.rate > 0.0 {
+                emitter.spawn_timer += dt * emitter.rate;
+                let steady = emitter.spawn_timer.floor();
+                emitter.spawn_timer -= steady;
+                count += steady as u32;
+            }
+            for _ in 0..count {
+                self.spawn(&mut particles, pos.current, &emitter.settings);
+            }
+        }
+
+        // --- Bursts ---
+        for burst in bursts.0.drain(..) {
+            let settings = burst.effect.settings(burst.direction);
+            for _ in 0..burst.effect.burst_count() {
+                self.spawn(&mut particles, burst.position, &settings);
+            }
+        }
+    }
+}
+
+impl ParticleSystem {
+    fn spawn(&mut self, particles: &mut Particles, position: Vector2<f32>, settings: &ParticleSettings) {
+        if particles.0.len() >= MAX_PARTICLES {
+            return;
+        }
+        let angle = settings.direction + (self.random() * 2.0 - 1.0) * settings.spread;
+        let speed = self.range(settings.speed);
+        particles.0.push(Particle {
+            position,
+            previous: position,
+            velocity: Vector2::new(angle.cos(), angle.sin()) * speed,
+            age: 0.0,
+            lifetime: self.range(settings.lifetime).max(1e-3),
+            settings: *settings,
+        });
+    }
+
+    /// A value picked evenly between the ends of `range`.
+    fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.random()
+    }
+
+    /// A pseudo-random value from 0.0 to 1.0.
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed >> 8) as f32 / (1 << 24) as f32
+    }
+}
@@ -1,22 +1,26 @@
 // src/systems/physics.rs
 
-use specs::{System, Write, WriteStorage, ReadStorage, Join};
+use specs::{System, Read, Write, WriteStorage, ReadStorage, Join};
 use crate::{
-    resources::PhysicsWorld,
-    components::{Position, PhysicsBody},
+    resources::{GameTime, PhysicsWorld},
+    components::{Position, Rotation, PhysicsBody},
 };
 
 pub struct PhysicsSystem;
 
 impl<'a> System<'a> for PhysicsSystem {
     type SystemData = (
+        Read<'a, GameTime>,
         Write<'a, PhysicsWorld>,
         WriteStorage<'a, Position>,
+        WriteStorage<'a, Rotation>,
         ReadStorage<'a, PhysicsBody>,
     );
 
-    fn run(&mut self, (mut physics_world, mut positions, bodies): Self::SystemData) {
+    fn run(&mut self, (time, mut physics_world, mut positions, mut rotations, bodies): Self::SystemData) {
         let pw = &mut *physics_world;
+        // Slow motion is simulated with shorter steps rather than fewer of them.
+        pw.integration_parameters.dt = time.dt;
 
         // Step the physics simulation forward one tick.
         pw.physics_pipeline.step(
@@ -42,12 +46,17 @@ impl<'a> System<'a> for PhysicsSystem {
 
 
         // After the simulation step, update the Position component of each entity
-        // to match its new physical location.
+        // to match its new physical location, keeping the old one for interpolation.
         for (pos, body) in (&mut positions, &bodies).join() {
             if let Some(rigid_body) = pw.rigid_body_set.get(body.rigid_body_handle) {
-                let translation = rigid_body.translation();
-                pos.0.x = translation.x;
-                pos.0.y = translation.y;
+                pos.previous = pos.current;
+                pos.current = *rigid_body.translation();
+            }
+        }
+        for (rot, body) in (&mut rotations, &bodies).join() {
+            if let Some(rigid_body) = pw.rigid_body_set.get(body.rigid_body_handle) {
+                rot.previous = rot.current;
+                rot.current = rigid_body.rotation().angle();
             }
         }
     }
@@ -2,38 +2,112 @@
 
 use specs::{System, Entities, Read, Write, ReadStorage, WriteStorage, Join};
 use crate::{
-    resources::{InputState, PhysicsWorld},
-    components::{PhysicsBody, Player, Grounded, CharacterController},
+    resources::{CameraShake, GameTime, HitEffect, InputState, ParticleBursts, PhysicsWorld},
+    components::{ParticleEffect, PhysicsBody, Player, Grounded, CharacterController, Swinging, SurfaceMaterial, GROUP_LEVEL, GROUP_ONE_WAY, GROUP_PLAYER, GROUP_PROP},
 };
 use winit::keyboard::KeyCode;
 use rapier2d::prelude::*;
+use rapier2d::control::CharacterCollision;
 use rapier2d::na::Vector2;
-use log;
+
+/// How far the player's feet may sink below the top of a one-way platform and still land on it.
+const ONE_WAY_TOLERANCE: f32 = 2.0;
+
+/// The downward speed a landing has to exceed before it shakes the camera.
+const HARD_LANDING_SPEED: f32 = 900.0;
+/// The downward speed at which a landing gives the camera maximum trauma.
+const MAX_LANDING_SPEED: f32 = 1800.0;
+
+/// The downward speed a landing has to exceed before it kicks up dust.
+const DUSTY_LANDING_SPEED: f32 = 300.0;
+
+/// How much speed a prop has to lose against the player before it counts as a hit.
+const HARD_HIT_SPEED: f32 = 400.0;
+/// The speed loss at which a hit is as strong as it gets.
+const MAX_HIT_SPEED: f32 = 1000.0;
+
+/// How quickly the player's horizontal speed follows the input, as the fraction of
+/// the difference made up each tick.
+const TRACTION: f32 = 0.2;
+/// The same on slippery ground, where the player slowly builds up speed and slides
+/// a long way before stopping.
+const SLIPPERY_TRACTION: f32 = 0.02;
+/// Ground with less friction than this is slippery, like ice.
+const SLIPPERY_FRICTION: f32 = 0.1;
+
+/// The horizontal acceleration the player can add to a swing by pumping left or right.
+const SWING_PUMP_ACCELERATION: f32 = 600.0;
 
 pub struct PlayerControlSystem;
 
 impl<'a> System<'a> for PlayerControlSystem {
     type SystemData = (
         Entities<'a>,
+        Read<'a, GameTime>,
         Read<'a, InputState>,
         Write<'a, PhysicsWorld>,
+        Write<'a, CameraShake>,
+        Write<'a, HitEffect>,
+        Write<'a, ParticleBursts>,
         ReadStorage<'a, Player>,
         ReadStorage<'a, PhysicsBody>,
         WriteStorage<'a, Grounded>,
         WriteStorage<'a, CharacterController>,
+        ReadStorage<'a, Swinging>,
     );
 
-    fn run(&mut self, (entities, input, mut physics, _players, bodies, mut grounded_storage, mut controllers): Self::SystemData) {
-        let dt = physics.integration_parameters.dt;
+    fn run(&mut self, (entities, time, input, mut physics, mut shake, mut hit, mut bursts, players, bodies, mut grounded_storage, mut controllers, swinging): Self::SystemData) {
+        let dt = time.dt;
+
+        let pressing_left = input.pressed_keys.contains(&KeyCode::KeyA) || input.pressed_keys.contains(&KeyCode::ArrowLeft);
+        let pressing_right = input.pressed_keys.contains(&KeyCode::KeyD) || input.pressed_keys.contains(&KeyCode::ArrowRight);
+
+        // --- Hits ---
+        // A prop that slams into the player hits them. How hard is told by how much
+        // speed the contact took off the prop during the last physics step.
+        let step_dt = physics.integration_parameters.dt;
+        while let Ok(event) = physics.contact_force_event_receiver.try_recv() {
+            let Some((_, body)) = (&players, &bodies).join()
+                .find(|(_, body)| body.collider_handle == event.collider1 || body.collider_handle == event.collider2) else {
+                continue;
+            };
+            let other = if body.collider_handle == event.collider1 { event.collider2 } else { event.collider1 };
+            let Some(prop) = physics.collider_set.get(other)
+                .and_then(|collider| collider.parent())
+                .and_then(|handle| physics.rigid_body_set.get(handle))
+                .filter(|rb| rb.is_dynamic()) else {
+                continue;
+            };
+            let speed_lost = event.total_force_magnitude * step_dt / prop.mass();
+            if speed_lost > HARD_HIT_SPEED {
+                let severity = ((speed_lost - HARD_HIT_SPEED) / (MAX_HIT_SPEED - HARD_HIT_SPEED)).min(1.0);
+                shake.add_trauma(0.2 + 0.4 * severity);
+                hit.add_hit(0.5 + 0.5 * severity);
+            }
+        }
 
-        for (entity, body, controller) in (&entities, &bodies, &mut controllers).join() {
+        // While swinging, the rigid body is dynamic and rapier moves it. The player
+        // can only pump the swing by pushing sideways.
+        for (body, _) in (&bodies, &swinging).join() {
+            let direction = if pressing_left { -1.0 } else if pressing_right { 1.0 } else { 0.0 };
+            if let Some(rb) = physics.rigid_body_set.get_mut(body.rigid_body_handle) {
+                let impulse = vector![direction * SWING_PUMP_ACCELERATION * rb.mass() * dt, 0.0];
+                rb.apply_impulse(impulse, true);
+            }
+        }
+
+        for (entity, body, controller, _) in (&entities, &bodies, &mut controllers, !&swinging).join() {
             // --- 1. Get Initial State ---
             let move_speed = 400.0;
             let jump_velocity = 700.0;
             let gravity = physics.gravity;
-            let filter = QueryFilter::new().exclude_rigid_body(body.rigid_body_handle);
+            // Sideways, the player only bumps into solid geometry and props.
+            let filter = QueryFilter::new()
+                .groups(InteractionGroups::new(GROUP_PLAYER, GROUP_LEVEL | GROUP_PROP))
+                .exclude_rigid_body(body.rigid_body_handle);
 
-            let is_grounded = grounded_storage.get(entity).is_some();
+            let ground_friction = grounded_storage.get(entity).map(|grounded| grounded.friction);
+            let is_grounded = ground_friction.is_some();
 
             // --- 2. Calculate Velocity ---
             // Apply gravity
@@ -44,10 +118,12 @@ impl<'a> System<'a> for PlayerControlSystem {
             }
 
             // Horizontal velocity
-            let pressing_left = input.pressed_keys.contains(&KeyCode::KeyA) || input.pressed_keys.contains(&KeyCode::ArrowLeft);
-            let pressing_right = input.pressed_keys.contains(&KeyCode::KeyD) || input.pressed_keys.contains(&KeyCode::ArrowRight);
             let desired_x_vel = if pressing_left { -move_speed } else if pressing_right { move_speed } else { 0.0 };
-            controller.velocity.x += (desired_x_vel - controller.velocity.x) * 0.2;
+            let traction = match ground_friction {
+                Some(friction) if friction < SLIPPERY_FRICTION => SLIPPERY_TRACTION,
+                _ => TRACTION,
+            };
+            controller.velocity.x += (desired_x_vel - controller.velocity.x) * traction;
 
             // --- JUMP LOGIC ---
             // **THE FIX**: A jump command overrides horizontal movement for one frame.
@@ -58,6 +134,7 @@ impl<'a> System<'a> for PlayerControlSystem {
                 // the character jumps straight up without interference from an adjacent wall.
                 // Air control will resume on the next frame.
                 controller.velocity.x = 0.0;
+                bursts.spawn(ParticleEffect::JumpPuff, feet_position(&physics, body), 0.0);
             }
 
             // --- 3. Separate Axis Movement ---
@@ -75,22 +152,54 @@ impl<'a> System<'a> for PlayerControlSystem {
             }
 
             // --- MOVE VERTICALLY ---
+            // One-way platforms only catch the player when falling onto them from above.
+            let falling = controller.velocity.y <= 0.0;
+            let feet = physics.collider_set.get(body.collider_handle).unwrap().shape()
+                .compute_aabb(&current_position).mins.y;
+            let one_way_predicate = |_, collider: &Collider| {
+                !collider.collision_groups().memberships.contains(GROUP_ONE_WAY)
+                    || (falling && collider.compute_aabb().maxs.y <= feet + ONE_WAY_TOLERANCE)
+            };
+            let vertical_filter = QueryFilter::new()
+                .groups(InteractionGroups::new(GROUP_PLAYER, GROUP_LEVEL | GROUP_ONE_WAY | GROUP_PROP))
+                .exclude_rigid_body(body.rigid_body_handle)
+                .predicate(&one_way_predicate);
+
             let vertical_movement = Vector2::new(0.0, controller.velocity.y * dt);
+            let mut ground = None;
             let vertical_collisions = controller.controller.move_shape(
                 dt, &physics.rigid_body_set, &physics.collider_set, &physics.query_pipeline,
                 physics.collider_set.get(body.collider_handle).unwrap().shape(),
-                &current_position, vertical_movement, filter, &mut |_| {},
+                &current_position, vertical_movement, vertical_filter, &mut |collision: CharacterCollision| ground = Some(collision.handle),
             );
             current_position.translation.vector += vertical_collisions.translation;
 
             // --- 4. Update State and Final Position ---
-            log::info!("[Movement] Grounded: {}, Vel: ({:.2}, {:.2}), Final Pos: ({:.2}, {:.2})", 
+            // Logged every tick, so it only shows with debug logging. F3 draws the same
+            // state over the game instead.
+            log::debug!("[Movement] Grounded: {}, Vel: ({:.2}, {:.2}), Final Pos: ({:.2}, {:.2})", 
                 vertical_collisions.grounded,
                 controller.velocity.x, controller.velocity.y, 
                 current_position.translation.x, current_position.translation.y);
 
             if vertical_collisions.grounded {
-                grounded_storage.insert(entity, Grounded).ok();
+                // Landing hard enough shakes the camera.
+                let landing_speed = -controller.velocity.y;
+                if !is_grounded && landing_speed > DUSTY_LANDING_SPEED {
+                    let feet = current_position.translation.vector - Vector2::new(0.0, feet_offset(&physics, body));
+                    bursts.spawn(ParticleEffect::Dust, feet, 0.0);
+                }
+                if !is_grounded && landing_speed > HARD_LANDING_SPEED {
+                    let severity = (landing_speed - HARD_LANDING_SPEED) / (MAX_LANDING_SPEED - HARD_LANDING_SPEED);
+                    shake.add_trauma(0.2 + 0.5 * severity.min(1.0));
+                }
+                // Snapping to the ground may not report a collision, so the player then
+                // keeps standing on whatever they stood on before.
+                let friction = ground.and_then(|handle| physics.collider_set.get(handle))
+                    .map(|collider| collider.friction())
+                    .or(ground_friction)
+                    .unwrap_or(SurfaceMaterial::default().friction());
+                grounded_storage.insert(entity, Grounded { friction }).ok();
             } else {
                 grounded_storage.remove(entity);
             }
@@ -108,3 +217,31 @@ impl<'a> System<'a> for PlayerControlSystem {
         }
     }
 }
+
+/// How far below the body's center its collider reaches.
+fn feet_offset(physics: &PhysicsWorld, body: &PhysicsBody) -> f32 {
+    physics.collider_set.get(body.collider_handle)
+        .map_or(0.0, |collider| -collider.shape().compute_local_aabb().mins.y)
+}
+
+/// The point under the middle of the body's collider, where it stands.
+pub fn feet_position(physics: &PhysicsWorld, body: &PhysicsBody) -> Vector2<f32> {
+    let center = physics.rigid_body_set.get(body.rigid_body_handle)
+        .map_or(Vector2::zeros(), |rb| *rb.translation());
+    center - Vector2::new(0.0, feet_offset(physics, body))
+}
+
+/// Hands the player's body over to rapier as a dynamic body, carrying the
+/// controller's velocity into the simulation so no momentum is lost.
+pub fn enter_swing_mode(rb: &mut RigidBody, controller: &CharacterController) {
+    rb.set_body_type(RigidBodyType::Dynamic, true);
+    rb.set_linvel(controller.velocity, true);
+}
+
+/// Takes the player's body back from the simulation as a kinematic body, carrying
+/// the swing's velocity into the character controller.
+pub fn enter_walk_mode(rb: &mut RigidBody, controller: &mut CharacterController) {
+    controller.velocity = *rb.linvel();
+    rb.set_body_type(RigidBodyType::KinematicPositionBased, true);
+    rb.set_linvel(Vector2::zeros(), true);
+}
@@ -1,50 +1,116 @@
 // src/systems/rendering.rs
 
-use specs::{System, Write, Read, ReadStorage, Join};
+use specs::{System, Write, Read, ReadStorage, Join, LendJoin};
 use crate::{
-    resources::{RenderData, Vertex, ScreenDimensions},
-    components::{Position, Renderable},
+    resources::{Camera, RenderData, RenderAlpha, Instance, Particles, ScreenDimensions},
+    components::{Position, Rotation, Renderable, RenderLayer, Sprite, Parallax},
+    atlas::{TextureAtlas, WHITE_REGION},
 };
 
-pub struct RenderingSystem;
+/// Turns every `Renderable` into a quad instance, ordered back to front by layer.
+#[derive(Default)]
+pub struct RenderingSystem {
+    /// This frame's instances tagged with their layers and parallax depths, kept
+    /// between frames to reuse the allocation.
+    queue: Vec<(RenderLayer, f32, Instance)>,
+}
 
 impl<'a> System<'a> for RenderingSystem {
     type SystemData = (
         Write<'a, RenderData>,
+        Read<'a, RenderAlpha>,
+        Read<'a, TextureAtlas>,
+        Read<'a, Camera>,
         Read<'a, ScreenDimensions>,
+        Read<'a, Particles>,
         ReadStorage<'a, Position>,
+        ReadStorage<'a, Rotation>,
         ReadStorage<'a, Renderable>,
+        ReadStorage<'a, Sprite>,
+        ReadStorage<'a, Parallax>,
     );
 
-    fn run(&mut self, (mut render_data, screen_dim, positions, renderables): Self::SystemData) {
-        // Clear the vertex data from the previous frame.
-        render_data.0.clear();
+    fn run(&mut self, (mut render_data, alpha, atlas, camera, screen, particles, positions, rotations, renderables, sprites, parallaxes): Self::SystemData) {
+        // Clear the instances from the previous frame.
+        render_data.instances.clear();
+        self.queue.clear();
+        let half_view = camera.half_extents(&screen);
 
         // Iterate over all entities that have both a Position and a Renderable component.
-        for (pos, render) in (&positions, &renderables).join() {
-            // This logic converts world coordinates (e.g., -500 to 500)
-            // into clip-space coordinates (-1.0 to 1.0) that the GPU expects.
-            let half_w = render.width / screen_dim.width;
-            let half_h = render.height / screen_dim.height;
-            let center_x = (pos.0.x / screen_dim.width) * 2.0;
-            let center_y = (pos.0.y / screen_dim.height) * 2.0;
-
-            let x_min = center_x - half_w;
-            let x_max = center_x + half_w;
-            let y_min = center_y - half_h;
-            let y_max = center_y + half_h;
-
-            // Create two triangles to form a quad.
-            let vertices = [
-                Vertex { position: [x_min, y_min], color: render.color },
-                Vertex { position: [x_max, y_min], color: render.color },
-                Vertex { position: [x_max, y_max], color: render.color },
-                Vertex { position: [x_min, y_min], color: render.color },
-                Vertex { position: [x_max, y_max], color: render.color },
-                Vertex { position: [x_min, y_max], color: render.color },
-            ];
-            // Add the vertices for this quad to the frame's render data.
-            render_data.0.extend_from_slice(&vertices);
+        for (pos, rot, render, sprite, parallax) in (&positions, rotations.maybe(), &renderables, sprites.maybe(), parallaxes.maybe()).join() {
+            // Draw the entity part of the way between its last two physics ticks,
+            // so motion stays smooth whatever the display's refresh rate.
+            let mut world_pos = pos.interpolated(alpha.0);
+            // Entities without a `Rotation` stay axis-aligned.
+            let angle = rot.map_or(0.0, |rot| rot.interpolated(alpha.0));
+
+            // Plain quads sample a white texel, so their color comes through as-is.
+            let region = atlas.region(sprite.map_or(WHITE_REGION, |s| s.region.as_str()));
+            let mut size = [render.width, render.height];
+            let mut uv_offset = [0.0, 0.0];
+
+            // --- Parallax ---
+            // Dragging the entity along with the part of the camera's movement it should
+            // ignore leaves it following only `factor` of it.
+            if let Some(parallax) = parallax {
+                let origin = world_pos + camera.position * (1.0 - parallax.factor);
+                world_pos = origin;
+                if parallax.repeat_x {
+                    // Cover the view even when the shake tilts it, and slide the texture
+                    // back so it stays put relative to the layer's origin.
+                    size[0] = 2.0 * half_view.norm();
+                    world_pos.x = camera.position.x;
+                    uv_offset[0] = (world_pos.x - size[0] / 2.0 - origin.x) / region.width as f32;
+                }
+            }
+
+            // Tiled sprites repeat once per image-sized patch of the quad.
+            let uv_scale = match sprite {
+                Some(s) if s.tiled => [size[0] / region.width as f32, size[1] / region.height as f32],
+                _ => [1.0, 1.0],
+            };
+
+            // Instances stay in world coordinates; the GPU shapes the unit quad
+            // around them and the camera's uniform takes it to clip space.
+            let depth = parallax.map_or(1.0, |parallax| parallax.factor);
+            self.queue.push((render.layer, depth, Instance {
+                position: world_pos.into(),
+                size,
+                rotation: angle,
+                color: render.color,
+                uv_rect: region.uv_rect(),
+                uv_scale,
+                uv_offset,
+            }));
+        }
+
+        // --- Particles ---
+        let white = atlas.region(WHITE_REGION).uv_rect();
+        for particle in &particles.0 {
+            let t = particle.progress();
+            let settings = &particle.settings;
+            let size = settings.size_start + (settings.size_end - settings.size_start) * t;
+            let mut color = settings.color_start;
+            for (channel, end) in color.iter_mut().zip(settings.color_end) {
+                *channel += (end - *channel) * t;
+            }
+            self.queue.push((settings.layer, 1.0, Instance {
+                position: particle.previous.lerp(&particle.position, alpha.0).into(),
+                size: [size, size],
+                rotation: 0.0,
+                color,
+                uv_rect: white,
+                uv_scale: [1.0, 1.0],
+                uv_offset: [0.0, 0.0],
+            }));
         }
+
+        // Within a layer, more distant parallax entities go first. The sort is stable,
+        // but the join order otherwise depends on entity IDs, so nothing should rely on
+        // the order of equally distant quads inside one layer.
+        self.queue.sort_by(|(layer_a, depth_a, _), (layer_b, depth_b, _)| {
+            layer_a.cmp(layer_b).then(depth_a.total_cmp(depth_b))
+        });
+        render_data.instances.extend(self.queue.drain(..).map(|(_, _, instance)| instance));
     }
 }
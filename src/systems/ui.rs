@@ -0,0 +1,62 @@
+// src/systems/ui.rs
+
+use specs::{System, Read, Write};
+use crate::{
+    resources::{Instance, RenderData, UiCommand, UiDrawList},
+    atlas::{TextureAtlas, WHITE_REGION},
+    font::{self, FONT_REGION, GLYPH_ASPECT},
+};
+
+/// Turns the frame's `UiDrawList` into quads on the UI layer, in the order they were queued.
+pub struct UiSystem;
+
+impl<'a> System<'a> for UiSystem {
+    type SystemData = (
+        Write<'a, RenderData>,
+        Write<'a, UiDrawList>,
+        Read<'a, TextureAtlas>,
+    );
+
+    fn run(&mut self, (mut render_data, mut draw_list, atlas): Self::SystemData) {
+        render_data.ui.clear();
+        let white = atlas.region(WHITE_REGION).uv_rect();
+        let font_region = atlas.region(FONT_REGION);
+
+        for command in draw_list.0.drain(..) {
+            match command {
+                UiCommand::Rect { pos, size, color } => {
+                    render_data.ui.push(screen_quad([pos.x, pos.y], [size.x, size.y], color, white));
+                }
+                UiCommand::Text { pos, size, color, text } => {
+                    // Snap to whole pixels so the glyphs' texels line up with the screen's.
+                    let advance = size * GLYPH_ASPECT;
+                    let (left, mut top) = (pos.x.round(), pos.y.round());
+                    for line in text.lines() {
+                        for (column, c) in line.chars().enumerate() {
+                            if c == ' ' {
+                                continue;
+                            }
+                            let x = left + column as f32 * advance;
+                            render_data.ui.push(screen_quad([x, top], [advance, size], color, font::glyph_uv_rect(&font_region, c)));
+                        }
+                        top += size;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A quad on the UI layer with its top-left corner at `pos`, in window pixels.
+fn screen_quad(pos: [f32; 2], size: [f32; 2], color: [f32; 4], uv_rect: [f32; 4]) -> Instance {
+    Instance {
+        position: [pos[0] + size[0] / 2.0, pos[1] + size[1] / 2.0],
+        // The UI's y axis points down, so the quad is flipped to keep images upright.
+        size: [size[0], -size[1]],
+        rotation: 0.0,
+        color,
+        uv_rect,
+        uv_scale: [1.0, 1.0],
+        uv_offset: [0.0, 0.0],
+    }
+}
@@ -0,0 +1,250 @@
+// src/ui.rs
+
+use std::ops::RangeInclusive;
+use winit::event::MouseButton;
+use rapier2d::na::Vector2;
+use crate::{
+    font,
+    resources::{InputState, UiCommand, UiDrawList},
+};
+
+/// How tall widget text is, in pixels.
+const TEXT_SIZE: f32 = 20.0;
+/// How tall buttons, toggles and sliders are, in pixels.
+const ROW_HEIGHT: f32 = 32.0;
+/// How tall each item of a list is, in pixels.
+const LIST_ROW_HEIGHT: f32 = 26.0;
+/// The space left between widgets, in pixels.
+const SPACING: f32 = 6.0;
+/// The space left around the widgets inside the panel, and around text inside widgets.
+const PADDING: f32 = 10.0;
+
+const PANEL_COLOR: [f32; 4] = [0.05, 0.05, 0.1, 0.85];
+const IDLE_COLOR: [f32; 4] = [0.2, 0.2, 0.3, 1.0];
+const FOCUS_COLOR: [f32; 4] = [0.35, 0.35, 0.6, 1.0];
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const ACCENT_COLOR: [f32; 4] = [1.0, 0.8, 0.3, 1.0];
+const TRACK_COLOR: [f32; 4] = [0.1, 0.1, 0.15, 1.0];
+
+/// What a `Ui` needs to remember between frames.
+#[derive(Default)]
+pub struct UiState {
+    /// The index of the focused widget, counting focusable widgets in the order they are laid out.
+    focused: usize,
+    /// How many focusable widgets the previous frame had, so focus can wrap around.
+    widget_count: usize,
+    /// The slider being dragged with the mouse, if any.
+    dragging: Option<usize>,
+    /// Where the cursor was on the previous frame. Only a moving cursor takes focus,
+    /// so a mouse left resting over a widget does not fight the keyboard.
+    last_cursor: Vector2<f32>,
+}
+
+impl UiState {
+    /// Forgets focus and dragging, for when a different screen is shown.
+    pub fn reset(&mut self) {
+        *self = Self { last_cursor: self.last_cursor, ..Self::default() };
+    }
+}
+
+/// An immediate-mode UI panel. Widgets are laid out top to bottom in the order they
+/// are called, each reporting whether it was used this frame, and are queued onto the
+/// UI layer as they go.
+///
+/// Up and down move the focus between widgets, left and right adjust the focused
+/// toggle or slider, and confirm activates it. The mouse focuses whatever it moves
+/// over and activates whatever it clicks.
+pub struct Ui<'a> {
+    state: &'a mut UiState,
+    input: &'a InputState,
+    draw_list: &'a mut UiDrawList,
+    /// The panel's top-left corner.
+    origin: Vector2<f32>,
+    width: f32,
+    /// Where the next widget's top edge goes.
+    cursor_y: f32,
+    /// The index the next focusable widget gets.
+    next_id: usize,
+    /// The draw command of the panel's background, sized once every widget is laid out.
+    panel: usize,
+    cursor_moved: bool,
+}
+
+/// Where one widget landed and how the player is interacting with it.
+struct Widget {
+    id: usize,
+    pos: Vector2<f32>,
+    size: Vector2<f32>,
+    focused: bool,
+    hovered: bool,
+}
+
+impl<'a> Ui<'a> {
+    /// Starts a panel `width` pixels wide with its top-left corner at `origin`.
+    pub fn begin(state: &'a mut UiState, input: &'a InputState, draw_list: &'a mut UiDrawList, origin: Vector2<f32>, width: f32) -> Self {
+        let count = state.widget_count;
+        if count > 0 {
+            if input.menu.down {
+                state.focused = (state.focused + 1) % count;
+            }
+            if input.menu.up {
+                state.focused = (state.focused + count - 1) % count;
+            }
+        }
+        if !input.pressed_mouse_buttons.contains(&MouseButton::Left) {
+            state.dragging = None;
+        }
+        let cursor_moved = input.cursor_position != state.last_cursor;
+        state.last_cursor = input.cursor_position;
+
+        let panel = draw_list.0.len();
+        draw_list.draw_rect(origin, Vector2::zeros(), PANEL_COLOR);
+        Self {
+            state,
+            input,
+            draw_list,
+            origin,
+            width,
+            cursor_y: origin.y + PADDING,
+            next_id: 0,
+            panel,
+            cursor_moved,
+        }
+    }
+
+    /// Finishes the panel, sizing its background to fit what was laid out.
+    pub fn end(self) {
+        self.state.widget_count = self.next_id;
+        self.state.focused = self.state.focused.min(self.next_id.saturating_sub(1));
+        if let UiCommand::Rect { size, .. } = &mut self.draw_list.0[self.panel] {
+            *size = Vector2::new(self.width, self.cursor_y - SPACING + PADDING - self.origin.y);
+        }
+    }
+
+    /// A line of text that cannot be focused.
+    pub fn label(&mut self, text: &str, color: [f32; 4]) {
+        let extent = font::measure_text(TEXT_SIZE, text);
+        let pos = Vector2::new(self.origin.x + (self.width - extent[0]) / 2.0, self.cursor_y);
+        self.draw_list.draw_text(pos, TEXT_SIZE, color, text);
+        self.cursor_y += extent[1] + SPACING;
+    }
+
+    /// A button with `text` on it. Returns true on the frame it is activated.
+    pub fn button(&mut self, text: &str) -> bool {
+        let widget = self.widget(ROW_HEIGHT);
+        self.background(&widget);
+        let extent = font::measure_text(TEXT_SIZE, text);
+        let pos = widget.pos + (widget.size - Vector2::new(extent[0], extent[1])) / 2.0;
+        self.draw_list.draw_text(pos, TEXT_SIZE, TEXT_COLOR, text);
+        self.activated(&widget)
+    }
+
+    /// A labelled on/off switch for `value`. Returns true on the frame it is flipped.
+    pub fn toggle(&mut self, text: &str, value: &mut bool) -> bool {
+        let widget = self.widget(ROW_HEIGHT);
+        let menu = self.input.menu;
+        let changed = self.activated(&widget) || (widget.focused && (menu.left || menu.right));
+        if changed {
+            *value = !*value;
+        }
+
+        self.background(&widget);
+        self.row_label(&widget, text, TEXT_COLOR);
+        let (state, color) = if *value { ("ON", ACCENT_COLOR) } else { ("OFF", TEXT_COLOR) };
+        self.row_value(&widget, state, color);
+        changed
+    }
+
+    /// A labelled slider setting `value` within `range`. The keyboard moves it by
+    /// `step`; the mouse drags it anywhere along the track. Returns true on every
+    /// frame `value` changes.
+    pub fn slider(&mut self, text: &str, value: &mut f32, range: RangeInclusive<f32>, step: f32) -> bool {
+        let widget = self.widget(ROW_HEIGHT);
+        let (min, max) = (*range.start(), *range.end());
+        let track_pos = widget.pos + Vector2::new(widget.size.x / 2.0, widget.size.y / 2.0 - 3.0);
+        let track_size = Vector2::new(widget.size.x / 2.0 - PADDING, 6.0);
+
+        let before = *value;
+        let menu = self.input.menu;
+        if widget.focused && menu.left {
+            *value -= step;
+        }
+        if widget.focused && menu.right {
+            *value += step;
+        }
+        // Only a click on the track starts a drag, so clicking the label just focuses.
+        if widget.hovered && menu.click && self.input.cursor_position.x >= track_pos.x - PADDING {
+            self.state.dragging = Some(widget.id);
+        }
+        if self.state.dragging == Some(widget.id) {
+            let t = (self.input.cursor_position.x - track_pos.x) / track_size.x;
+            *value = min + (max - min) * t.clamp(0.0, 1.0);
+        }
+        *value = value.clamp(min, max);
+
+        self.background(&widget);
+        self.row_label(&widget, text, TEXT_COLOR);
+        let t = if max > min { (*value - min) / (max - min) } else { 0.0 };
+        self.draw_list.draw_rect(track_pos, track_size, TRACK_COLOR);
+        self.draw_list.draw_rect(track_pos, Vector2::new(track_size.x * t, track_size.y), ACCENT_COLOR);
+        let knob = Vector2::new(6.0, widget.size.y - 8.0);
+        self.draw_list.draw_rect(Vector2::new(track_pos.x + track_size.x * t - knob.x / 2.0, widget.pos.y + 4.0), knob, TEXT_COLOR);
+        *value != before
+    }
+
+    /// A vertical list of `items` that each can be picked, with the picked one
+    /// marked. Returns true on the frame `selected` changes.
+    pub fn list(&mut self, items: &[&str], selected: &mut usize) -> bool {
+        let mut changed = false;
+        for (index, item) in items.iter().enumerate() {
+            let widget = self.widget(LIST_ROW_HEIGHT);
+            if self.activated(&widget) && *selected != index {
+                *selected = index;
+                changed = true;
+            }
+            self.background(&widget);
+            let (marker, color) = if *selected == index { ("> ", ACCENT_COLOR) } else { ("  ", TEXT_COLOR) };
+            self.row_label(&widget, &format!("{marker}{item}"), color);
+        }
+        changed
+    }
+
+    /// Lays out the next focusable widget, `height` pixels tall and as wide as the panel.
+    fn widget(&mut self, height: f32) -> Widget {
+        let id = self.next_id;
+        self.next_id += 1;
+        let pos = Vector2::new(self.origin.x + PADDING, self.cursor_y);
+        let size = Vector2::new(self.width - PADDING * 2.0, height);
+        self.cursor_y += height + SPACING;
+
+        let cursor = self.input.cursor_position;
+        let hovered = cursor.x >= pos.x && cursor.x < pos.x + size.x && cursor.y >= pos.y && cursor.y < pos.y + size.y;
+        if hovered && (self.cursor_moved || self.input.menu.click) {
+            self.state.focused = id;
+        }
+        Widget { id, pos, size, focused: self.state.focused == id, hovered }
+    }
+
+    /// Whether `widget` was clicked or confirmed this frame.
+    fn activated(&self, widget: &Widget) -> bool {
+        (widget.hovered && self.input.menu.click) || (widget.focused && self.input.menu.confirm)
+    }
+
+    fn background(&mut self, widget: &Widget) {
+        let color = if widget.focused { FOCUS_COLOR } else { IDLE_COLOR };
+        self.draw_list.draw_rect(widget.pos, widget.size, color);
+    }
+
+    /// Draws `text` at the left end of a row.
+    fn row_label(&mut self, widget: &Widget, text: &str, color: [f32; 4]) {
+        let pos = widget.pos + Vector2::new(PADDING, (widget.size.y - TEXT_SIZE) / 2.0);
+        self.draw_list.draw_text(pos, TEXT_SIZE, color, text);
+    }
+
+    /// Draws `text` at the right end of a row.
+    fn row_value(&mut self, widget: &Widget, text: &str, color: [f32; 4]) {
+        let extent = font::measure_text(TEXT_SIZE, text);
+        let pos = widget.pos + Vector2::new(widget.size.x - PADDING - extent[0], (widget.size.y - TEXT_SIZE) / 2.0);
+        self.draw_list.draw_text(pos, TEXT_SIZE, color, text);
+    }
+}